@@ -0,0 +1,88 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use minesweeper::batch::{generate, generated_boards};
+use minesweeper::{check_configuration, ProbeResult, SolveOptions, Solver, Strategy};
+
+fn bench_difficulties(c: &mut Criterion) {
+    let mut group = c.benchmark_group("difficulty");
+    let difficulties = [
+        ("beginner", 9, 9, 10),
+        ("intermediate", 16, 16, 40),
+        ("expert", 16, 30, 99),
+    ];
+
+    for (name, rows, cols, mines) in difficulties.iter() {
+        let board = generate(*rows, *cols, *mines, 42);
+        group.bench_with_input(BenchmarkId::from_parameter(name), &board, |b, board| {
+            b.iter(|| check_configuration(board.clone()))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_density(c: &mut Criterion) {
+    let mut group = c.benchmark_group("density");
+    let (rows, cols) = (16, 16);
+
+    for percent in [10, 15, 20, 25, 30].iter() {
+        let mines = rows * cols * percent / 100;
+        let board = generate(rows, cols, mines, 42);
+        group.bench_with_input(BenchmarkId::from_parameter(percent), &board, |b, board| {
+            b.iter(|| check_configuration(board.clone()))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_engines(c: &mut Criterion) {
+    let mut group = c.benchmark_group("engines");
+    let strategies = [
+        ("rules", Strategy::RulesOnly),
+        ("search", Strategy::SearchOnly),
+        (
+            "hybrid",
+            Strategy::Hybrid {
+                frontier_limit: usize::MAX,
+            },
+        ),
+    ];
+
+    // The same boards as the other groups
+    for (name, board) in generated_boards(42) {
+        let solvers: Vec<(&str, Solver)> = strategies
+            .iter()
+            .map(|(engine, strategy)| {
+                let options = SolveOptions::new().with_strategy(*strategy);
+                (*engine, Solver::new().with_options(options))
+            })
+            .collect();
+
+        // The engines may fail to decide a probe, but never decide it differently
+        let results: Vec<ProbeResult> = solvers
+            .iter()
+            .map(|(_, solver)| solver.check(&board).result)
+            .collect();
+        for a in &results {
+            for b in &results {
+                assert!(
+                    a == b || *a == ProbeResult::Unknown || *b == ProbeResult::Unknown,
+                    "The engines disagree on {}: {:?}",
+                    name,
+                    results
+                );
+            }
+        }
+
+        for (engine, solver) in &solvers {
+            group.bench_with_input(BenchmarkId::new(*engine, &name), &board, |b, board| {
+                b.iter(|| solver.check(board))
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_difficulties, bench_density, bench_engines);
+criterion_main!(benches);
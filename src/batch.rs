@@ -0,0 +1,206 @@
+//! Batch analysis of a corpus of board files or of generated boards, comparing the rule engine
+//! with the exhaustive search of `exact_verdict`. Disagreements between the two point at bugs
+//! in the rule engine.
+
+use crate::protocol::catch_panic;
+use crate::{
+    check_board_with, exact_verdict_with, Configuration, MineLayout, ProbeResult, Seeded,
+    SolveOptions,
+};
+use serde::Serialize;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Instant;
+
+/// The analysis of a single board file
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Record {
+    pub file: String,
+    /// Verdict of the rule engine
+    pub rules: Option<ProbeResult>,
+    /// Verdict of the exhaustive search, which doesn't support anti-mines.
+    /// Both engines report `TimedOut` when they run out of time.
+    pub search: Option<ProbeResult>,
+    pub rules_us: u128,
+    pub search_us: u128,
+    /// The rule engine decided the probe, but the search disagrees
+    pub disagreement: bool,
+    pub error: Option<String>,
+}
+
+impl Record {
+    fn new(file: String) -> Record {
+        Record {
+            file,
+            rules: None,
+            search: None,
+            rules_us: 0,
+            search_us: 0,
+            disagreement: false,
+            error: None,
+        }
+    }
+}
+
+/// Analyzes every file in `dir`, in the order of their names
+pub fn analyze_dir(dir: &Path) -> io::Result<Vec<Record>> {
+    analyze_dir_with(dir, &SolveOptions::default())
+}
+
+/// Same as `analyze_dir`, but each engine gives up on a board when `options` say so
+pub fn analyze_dir_with(dir: &Path, options: &SolveOptions) -> io::Result<Vec<Record>> {
+    let mut paths = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut records = vec![];
+    for path in paths {
+        let file = path
+            .file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+        records.push(analyze_with(file, fs::read_to_string(&path)?, options));
+    }
+    Ok(records)
+}
+
+/// Analyzes the board `raw` read from `file` with both engines
+pub fn analyze(file: String, raw: String) -> Record {
+    analyze_with(file, raw, &SolveOptions::default())
+}
+
+/// Same as `analyze`, but each engine gives up when `options` say so. The strategy is ignored.
+pub fn analyze_with(file: String, raw: String, options: &SolveOptions) -> Record {
+    match catch_panic(|| Configuration::from(raw.trim().to_string())) {
+        Ok(conf) => analyze_configuration(file, &conf, options),
+        Err(error) => Record {
+            error: Some(error),
+            ..Record::new(file)
+        },
+    }
+}
+
+/// Analyzes `conf`, named `file` in the record, with both engines giving up when `options`
+/// say so
+pub fn analyze_configuration(file: String, conf: &Configuration, options: &SolveOptions) -> Record {
+    let mut record = Record::new(file);
+
+    let start = Instant::now();
+    match catch_panic(|| check_board_with(conf, options)) {
+        Ok(verdict) => record.rules = Some(verdict),
+        Err(error) => {
+            record.error = Some(error);
+            return record;
+        }
+    }
+    record.rules_us = start.elapsed().as_micros();
+
+    if !conf.anti_mines() {
+        let start = Instant::now();
+        record.search = match exact_verdict_with(conf, options) {
+            Ok(verdict) => verdict,
+            Err(interruption) => Some(interruption.into()),
+        };
+        record.search_us = start.elapsed().as_micros();
+        if record.search.is_none() {
+            record.error = Some(String::from("Inconsistent board"));
+        }
+    }
+
+    record.disagreement = match (record.rules, record.search) {
+        (Some(ProbeResult::Safe), Some(search)) => search != ProbeResult::Safe,
+        (Some(ProbeResult::Unsafe), Some(search)) => search != ProbeResult::Unsafe,
+        _ => false,
+    };
+    record
+}
+
+/// Generates a consistent board with `mines` mines, where about half of the mine-free cells
+/// are revealed and one of the remaining covered cells is a probe
+pub fn generate(rows: usize, cols: usize, mines: usize, seed: u64) -> Configuration {
+    let mut rng = Seeded::new(seed);
+    let layout = MineLayout::random(rows, cols, mines, &mut rng);
+
+    let mut revealed = vec![vec![false; cols]; rows];
+    let mut covered = vec![];
+    for (row, line) in revealed.iter_mut().enumerate() {
+        for (col, cell) in line.iter_mut().enumerate() {
+            if layout.is_mine(row, col) || rng.chance(0.5) {
+                covered.push((row, col));
+            } else {
+                *cell = true;
+            }
+        }
+    }
+
+    let probe = covered[rng.below(covered.len())];
+    layout.configuration(|row, col| revealed[row][col], probe)
+}
+
+/// Generates the boards of the benchmarks from `seed`: beginner, intermediate and expert games,
+/// and 16x16 boards with 10% to 30% of mines
+pub fn generated_boards(seed: u64) -> Vec<(String, Configuration)> {
+    let mut boards = vec![];
+    for (name, rows, cols, mines) in [
+        ("beginner", 9, 9, 10),
+        ("intermediate", 16, 16, 40),
+        ("expert", 16, 30, 99),
+    ] {
+        boards.push((name.to_string(), generate(rows, cols, mines, seed)));
+    }
+    for percent in [10, 15, 20, 25, 30] {
+        let mines = 16 * 16 * percent / 100;
+        let name = format!("density-{}", percent);
+        boards.push((name, generate(16, 16, mines, seed)));
+    }
+    boards
+}
+
+/// Writes `records` as CSV with a header line
+pub fn write_csv(records: &[Record], mut output: impl Write) -> io::Result<()> {
+    writeln!(
+        output,
+        "file,rules,search,rules_us,search_us,disagreement,error"
+    )?;
+    for record in records {
+        writeln!(
+            output,
+            "{},{},{},{},{},{},{}",
+            csv_field(&record.file),
+            verdict_name(record.rules),
+            verdict_name(record.search),
+            record.rules_us,
+            record.search_us,
+            record.disagreement,
+            csv_field(record.error.as_deref().unwrap_or("")),
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes `records` as a JSON array
+pub fn write_json(records: &[Record], mut output: impl Write) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut output, records)?;
+    writeln!(output)
+}
+
+fn verdict_name(verdict: Option<ProbeResult>) -> String {
+    match verdict.map(serde_json::to_value) {
+        Some(Ok(serde_json::Value::String(name))) => name,
+        _ => String::new(),
+    }
+}
+
+/// Quotes `field` if it contains separators, quotes or line breaks
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,77 @@
+//! Anytime analysis of a whole board, so that interactive assistants can show results
+//! as soon as they are known and refine them later.
+
+use crate::{deduce_with, search, Col, Configuration, ProbeResult, Row, SolveOptions, Square};
+use std::cmp::Reverse;
+use std::time::{Duration, Instant};
+
+/// The outcome of `analyze_with_budget`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PartialAnalysis {
+    /// Covered cells next to numbers analyzed in time, sorted by position.
+    /// `Unknown` cells are proven to be undecidable.
+    pub cells: Vec<(Row, Col, ProbeResult)>,
+    /// Covered cells next to numbers not analyzed in time, sorted by position
+    pub unsolved: Vec<(Row, Col)>,
+}
+
+impl PartialAnalysis {
+    /// Whether every covered cell next to a number was analyzed
+    pub fn is_complete(&self) -> bool {
+        self.unsolved.is_empty()
+    }
+}
+
+/// Analyzes the covered cells next to the numbers of `conf` for about `budget`: the rule engine
+/// runs first, then the exhaustive search of `exact_verdict` checks the cells the rules couldn't
+/// decide, the ones next to the most numbers first. The search stops as soon as the budget
+/// runs out, leaving the cell it was checking unsolved. The search doesn't support anti-mines,
+/// and leaves every cell of an inconsistent board the rules couldn't decide unsolved.
+/// Cells away from the numbers are never decidable and are left out.
+pub fn analyze_with_budget(conf: &Configuration, budget: Duration) -> PartialAnalysis {
+    let start = Instant::now();
+    let frontier = search::frontier(conf);
+
+    let options = SolveOptions::new().with_timeout(budget);
+    let verified = match deduce_with(conf, &options) {
+        Ok(verified) => verified,
+        Err(_) => {
+            let mut unsolved: Vec<(Row, Col)> =
+                frontier.into_iter().map(|(cell, _)| cell).collect();
+            unsolved.sort();
+            return PartialAnalysis {
+                cells: vec![],
+                unsolved,
+            };
+        }
+    };
+
+    let mut analysis = PartialAnalysis::default();
+    let mut undecided = vec![];
+    for ((row, col), numbers) in frontier {
+        match verified.get(&(row, col)) {
+            Some(Square::Safe) => analysis.cells.push((row, col, ProbeResult::Safe)),
+            Some(_) => analysis.cells.push((row, col, ProbeResult::Unsafe)),
+            None => undecided.push(((row, col), numbers)),
+        }
+    }
+
+    // Cells next to more numbers are more likely to be decided
+    undecided.sort_by_key(|&(cell, numbers)| (Reverse(numbers), cell));
+    for ((row, col), _) in undecided {
+        let verdict = if conf.anti_mines() {
+            None
+        } else {
+            let conf = conf.clone().with_probe(row, col);
+            search::exact_verdict_since(&conf, &options, start).unwrap_or(None)
+        };
+        match verdict {
+            Some(verdict) => analysis.cells.push((row, col, verdict)),
+            None => analysis.unsolved.push((row, col)),
+        }
+    }
+
+    analysis.cells.sort_by_key(|&(row, col, _)| (row, col));
+    analysis.unsolved.sort();
+    analysis
+}
@@ -0,0 +1,192 @@
+//! Caching of verdicts by the constraints around the probe. Frontier fragments repeat a lot
+//! across games, so long bot runs can skip re-solving them, see `Solver::with_cache`.
+
+use crate::{find_probe, Col, Configuration, EngineKind, Label, ProbeResult, Row, Square};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// How well a `Solver` cache performed
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+    /// Patterns currently cached
+    pub len: usize,
+}
+
+/// A least recently used cache of verdicts keyed by `canonical_key`
+#[derive(Clone, Debug)]
+pub(crate) struct PatternCache {
+    capacity: usize,
+    entries: HashMap<String, (ProbeResult, EngineKind, u64)>,
+    /// Keys by the time of their last use, the least recently used first
+    order: BTreeMap<u64, String>,
+    clock: u64,
+    hits: usize,
+    misses: usize,
+}
+
+impl PatternCache {
+    pub(crate) fn new(capacity: usize) -> PatternCache {
+        if capacity == 0 {
+            panic!("Invalid cache capacity: {}", capacity);
+        }
+        PatternCache {
+            capacity,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            clock: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub(crate) fn get(&mut self, key: &str) -> Option<(ProbeResult, EngineKind)> {
+        self.clock += 1;
+        match self.entries.get_mut(key) {
+            Some((result, engine, used)) => {
+                self.order.remove(used);
+                self.order.insert(self.clock, key.to_string());
+                *used = self.clock;
+                self.hits += 1;
+                Some((*result, *engine))
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub(crate) fn insert(&mut self, key: String, result: ProbeResult, engine: EngineKind) {
+        self.clock += 1;
+        if let Some((_, _, used)) = self.entries.remove(&key) {
+            self.order.remove(&used);
+        } else if self.entries.len() == self.capacity {
+            if let Some((_, oldest)) = self.order.pop_first() {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.order.insert(self.clock, key.clone());
+        self.entries.insert(key, (result, engine, self.clock));
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            len: self.entries.len(),
+        }
+    }
+}
+
+/// Maps a cell to its image under a rotation or reflection of the plane
+type Symmetry = fn(isize, isize) -> (isize, isize);
+
+const SYMMETRIES: [Symmetry; 8] = [
+    |r, c| (r, c),
+    |r, c| (c, -r),
+    |r, c| (-r, -c),
+    |r, c| (-c, r),
+    |r, c| (r, -c),
+    |r, c| (-r, c),
+    |r, c| (c, r),
+    |r, c| (-c, -r),
+];
+
+/// Describes the constraints the numbers of `conf` impose on the covered cells connected to
+/// the probe, i.e. the constraints sharing cells with the probe or with each other, together with
+/// the probe. The verdict only depends on these, so boards whose constraints only differ by
+/// a translation, rotation or reflection share a key, whatever the rest of the board.
+/// `search` tells whether the search may decide the probe if the rules can't, which depends on
+/// the whole board for `Strategy::Hybrid`. Assumes that the board is consistent.
+pub(crate) fn canonical_key(conf: &Configuration, search: bool) -> String {
+    let probe = find_probe(conf);
+
+    let mut constraints = vec![];
+    for (row, squares) in conf.board.iter().enumerate() {
+        for (col, square) in squares.iter().enumerate() {
+            if let Square::Number(n) = square {
+                let mut cells = vec![];
+                let mut mines = *n;
+                for (r, c) in conf.neighbours(row, col) {
+                    match conf.board[r][c] {
+                        Square::Mine => mines -= 1,
+                        Square::AntiMine => mines += 1,
+                        Square::Empty | Square::Probe => cells.push((r, c)),
+                        _ => {}
+                    }
+                }
+                // The rule engine ignores these as well
+                if !cells.is_empty() && (conf.anti_mines() || mines >= 0) {
+                    constraints.push((cells, mines));
+                }
+            }
+        }
+    }
+
+    // Other constraints don't share cells with these, so they can't affect the probe
+    let mut connected = HashSet::from([probe]);
+    let mut component = vec![];
+    loop {
+        let (linked, rest): (Vec<_>, Vec<_>) = constraints
+            .into_iter()
+            .partition(|(cells, _)| cells.iter().any(|cell| connected.contains(cell)));
+        constraints = rest;
+        if linked.is_empty() {
+            break;
+        }
+        for (cells, _) in &linked {
+            connected.extend(cells.iter().copied());
+        }
+        component.extend(linked);
+    }
+    let constraints = component;
+
+    SYMMETRIES
+        .iter()
+        .map(|symmetry| {
+            let transform = |(row, col): (Row, Col)| symmetry(row as isize, col as isize);
+            let mut transformed: Vec<(Vec<(isize, isize)>, Label)> = constraints
+                .iter()
+                .map(|(cells, mines)| {
+                    let cells = cells.iter().map(|cell| transform(*cell)).collect();
+                    (cells, *mines)
+                })
+                .collect();
+            let probe = transform(probe);
+
+            // Translate the cells so that the smallest coordinates are zero
+            let all = || {
+                transformed
+                    .iter()
+                    .flat_map(|(cells, _)| cells)
+                    .chain([&probe])
+            };
+            let min_row = all().map(|(row, _)| *row).min().unwrap();
+            let min_col = all().map(|(_, col)| *col).min().unwrap();
+            for (cells, _) in transformed.iter_mut() {
+                for cell in cells.iter_mut() {
+                    *cell = (cell.0 - min_row, cell.1 - min_col);
+                }
+                cells.sort();
+            }
+            transformed.sort();
+
+            let mut key = format!(
+                "anti-mines: {}\nsearch: {}\n?: {:?}\n",
+                conf.anti_mines(),
+                search,
+                (probe.0 - min_row, probe.1 - min_col)
+            );
+            for (cells, mines) in transformed {
+                key += &format!("{:?} = {}\n", cells, mines);
+            }
+            key
+        })
+        .min()
+        .unwrap()
+}
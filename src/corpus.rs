@@ -0,0 +1,45 @@
+//! Canonical boards with known analyses: games from beginner to expert, tricky patterns and
+//! inconsistent boards. The expected verdicts are kept as snapshots in `src/test/snapshots`,
+//! so that changes to the engines are checked against every cell of these boards.
+//! The board files live in the `corpus` directory, which `batch` mode can analyze as well.
+
+use crate::Configuration;
+
+/// A board of the corpus
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub name: &'static str,
+    /// The board in the text format
+    pub raw: &'static str,
+}
+
+impl Entry {
+    pub fn configuration(&self) -> Configuration {
+        Configuration::from(self.raw.trim().to_string())
+    }
+}
+
+macro_rules! entry {
+    ($name:literal) => {
+        Entry {
+            name: $name,
+            raw: include_str!(concat!("../corpus/", $name, ".txt")),
+        }
+    };
+}
+
+/// All boards of the corpus
+pub const BOARDS: &[Entry] = &[
+    entry!("beginner"),
+    entry!("intermediate"),
+    entry!("expert"),
+    entry!("one_one_one"),
+    entry!("one_two_one"),
+    entry!("one_two_two_one"),
+    entry!("chained_subsets"),
+    entry!("beyond_the_rules"),
+    entry!("no_numbers"),
+    entry!("anti_mines"),
+    entry!("inconsistent_count"),
+    entry!("inconsistent_mine"),
+];
@@ -0,0 +1,181 @@
+//! Rendering boards as images in the classic minesweeper style.
+//! Every topology is drawn as a square grid.
+
+use crate::{deduce, Configuration, Label, Square};
+use std::fmt::Write;
+
+/// Options of the rendered image
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SvgOptions {
+    /// Side of a cell in pixels
+    pub cell_size: u32,
+    /// Whether to tint the covered cells the solver proves safe (green) or unsafe (red)
+    pub overlay: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> SvgOptions {
+        SvgOptions {
+            cell_size: 24,
+            overlay: false,
+        }
+    }
+}
+
+/// Renders `conf` as an SVG document
+pub fn to_svg(conf: &Configuration, options: &SvgOptions) -> String {
+    let size = options.cell_size as usize;
+    let verified = if options.overlay {
+        deduce(conf)
+    } else {
+        Default::default()
+    };
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {0} {1}">"#,
+        conf.cols() * size,
+        conf.rows() * size
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<style>text {{ font: bold {}px sans-serif; text-anchor: middle; dominant-baseline: central; }}</style>"#,
+        size * 2 / 3
+    )
+    .unwrap();
+
+    for (row, squares) in conf.board.iter().enumerate() {
+        for (col, square) in squares.iter().enumerate() {
+            let (x, y) = (col * size, row * size);
+            let (cx, cy) = (x + size / 2, y + size / 2);
+
+            match square {
+                Square::Empty | Square::Probe => covered(&mut svg, x, y, size),
+                _ => revealed(&mut svg, x, y, size),
+            }
+
+            match square {
+                Square::Mine => {
+                    writeln!(svg, r#"<circle cx="{}" cy="{}" r="{}" fill="black"/>"#, cx, cy, size / 4).unwrap()
+                }
+                Square::AntiMine => writeln!(
+                    svg,
+                    r#"<circle cx="{}" cy="{}" r="{}" fill="white" stroke="black" stroke-width="2"/>"#,
+                    cx,
+                    cy,
+                    size / 4
+                )
+                .unwrap(),
+                Square::Probe => {
+                    writeln!(svg, r#"<text x="{}" y="{}" fill="black">?</text>"#, cx, cy).unwrap()
+                }
+                Square::Number(n) if *n != 0 => writeln!(
+                    svg,
+                    r#"<text x="{}" y="{}" fill="{}">{}</text>"#,
+                    cx,
+                    cy,
+                    number_color(*n),
+                    n
+                )
+                .unwrap(),
+                _ => {}
+            }
+
+            let tint = match verified.get(&(row, col)) {
+                Some(Square::Safe) => "green",
+                Some(_) => "red",
+                None => continue,
+            };
+            writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{}" height="{2}" fill="{}" fill-opacity="0.4"/>"#,
+                x, y, size, tint
+            )
+            .unwrap();
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// A raised gray cell with a white top left bevel and a dark bottom right one
+fn covered(svg: &mut String, x: usize, y: usize, size: usize) {
+    let b = (size / 8).max(1);
+    let (right, bottom) = (x + size, y + size);
+    writeln!(
+        svg,
+        r##"<rect x="{}" y="{}" width="{}" height="{2}" fill="#c0c0c0"/>"##,
+        x, y, size
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<polygon points="{x},{y} {r},{y} {ri},{yi} {xi},{yi} {xi},{bi} {x},{b}" fill="white"/>"#,
+        x = x,
+        y = y,
+        r = right,
+        b = bottom,
+        xi = x + b,
+        yi = y + b,
+        ri = right - b,
+        bi = bottom - b
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r##"<polygon points="{r},{b} {x},{b} {xi},{bi} {ri},{bi} {ri},{yi} {r},{y}" fill="#808080"/>"##,
+        x = x,
+        y = y,
+        r = right,
+        b = bottom,
+        xi = x + b,
+        yi = y + b,
+        ri = right - b,
+        bi = bottom - b
+    )
+    .unwrap();
+}
+
+/// A flat cell with a thin grid line
+fn revealed(svg: &mut String, x: usize, y: usize, size: usize) {
+    writeln!(
+        svg,
+        r##"<rect x="{}" y="{}" width="{}" height="{2}" fill="#bdbdbd" stroke="#7b7b7b" stroke-width="1"/>"##,
+        x, y, size
+    )
+    .unwrap();
+}
+
+fn number_color(n: Label) -> &'static str {
+    match n {
+        1 => "#0000ff",
+        2 => "#008000",
+        3 => "#ff0000",
+        4 => "#000080",
+        5 => "#800000",
+        6 => "#008080",
+        7 => "#000000",
+        8 => "#808080",
+        // Negative numbers of the anti-mine variant and larger numbers of custom topologies
+        _ => "#800080",
+    }
+}
+
+/// Renders `conf` as a PNG image, using the system fonts for the numbers
+#[cfg(feature = "png")]
+pub fn to_png(conf: &Configuration, options: &SvgOptions) -> Vec<u8> {
+    use resvg::{tiny_skia, usvg};
+
+    let mut opt = usvg::Options::default();
+    opt.fontdb_mut().load_system_fonts();
+    let tree = usvg::Tree::from_str(&to_svg(conf, options), &opt).expect("Invalid SVG");
+
+    let size = tree.size().to_int_size();
+    let mut pixmap =
+        tiny_skia::Pixmap::new(size.width(), size.height()).expect("The board is empty");
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+    pixmap.encode_png().expect("Failed to encode PNG")
+}
@@ -0,0 +1,109 @@
+//! An HTTP API over the solver, enabled by the `server` feature.
+//!
+//! `POST /analyze` takes `{"board": "<board in the text format>"}`, optionally with a
+//! `"timeout_ms"` limit, and returns the same analysis as the `analyze` command of the JSON
+//! protocol, or `400 Bad Request` with `{"error": "..."}` for invalid boards. A board the solver
+//! doesn't analyze in time gets a `timed_out` probe.
+//!
+//! `POST /generate` takes `{"rows": 9, "cols": 9, "mines": 10, "seed": 1}` and returns
+//! `{"board": "..."}`, a consistent board in the text format with about half of the mine-free
+//! cells revealed and a probe, which can be sent to `/analyze` as it is. The same seed always
+//! generates the same board.
+//!
+//! Unexpected failures are reported as `500 Internal Server Error`.
+
+use crate::batch;
+use crate::protocol::{self, Analysis};
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::io;
+use tokio::net::TcpListener;
+use tokio::task;
+
+#[derive(Debug, Deserialize)]
+struct AnalyzeRequest {
+    board: String,
+    timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GenerateRequest {
+    rows: usize,
+    cols: usize,
+    mines: usize,
+    seed: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct Board {
+    board: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Error {
+    error: String,
+}
+
+type Failure = (StatusCode, Json<Error>);
+
+/// The largest board `/generate` accepts, so that a request can't exhaust the memory
+const MAX_CELLS: usize = 1_000_000;
+
+/// Returns the routes of the API
+pub fn router() -> Router {
+    Router::new()
+        .route("/analyze", post(analyze))
+        .route("/generate", post(generate))
+}
+
+/// Serves the API on `address` until the process is stopped
+pub async fn serve(address: &str) -> io::Result<()> {
+    let listener = TcpListener::bind(address).await?;
+    axum::serve(listener, router()).await
+}
+
+async fn analyze(Json(request): Json<AnalyzeRequest>) -> Result<Json<Analysis>, Failure> {
+    blocking(move || protocol::analyze(request.board, request.timeout_ms)).await
+}
+
+async fn generate(Json(request): Json<GenerateRequest>) -> Result<Json<Board>, Failure> {
+    let GenerateRequest {
+        rows,
+        cols,
+        mines,
+        seed,
+    } = request;
+    blocking(move || {
+        let cells = rows.checked_mul(cols).filter(|cells| *cells <= MAX_CELLS);
+        match cells {
+            None => Err(format!("Board too large: {}x{}", rows, cols)),
+            // The probe needs a covered cell, i.e. a mine or a hidden mine-free cell
+            Some(cells) if mines == 0 || mines >= cells => {
+                Err(format!("Invalid number of mines: {}", mines))
+            }
+            Some(_) => Ok(Board {
+                board: batch::generate(rows, cols, mines, seed).to_string(),
+            }),
+        }
+    })
+    .await
+}
+
+/// Runs `f` on a thread of its own instead of a runtime worker, as the solver may run for long,
+/// answering `400 Bad Request` for its errors
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<Json<T>, Failure> {
+    match task::spawn_blocking(f).await {
+        Ok(Ok(result)) => Ok(Json(result)),
+        Ok(Err(error)) => Err((StatusCode::BAD_REQUEST, Json(Error { error }))),
+        Err(error) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(Error {
+                error: format!("Request failed: {}", error),
+            }),
+        )),
+    }
+}
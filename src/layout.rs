@@ -0,0 +1,148 @@
+use crate::topology::{Square8, Topology};
+use crate::{Col, Configuration, Label, Row, Seeded, Square};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// The ground-truth placement of mines behind a board configuration
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MineLayout {
+    mines: Vec<Vec<bool>>,
+}
+
+impl MineLayout {
+    pub fn new(mines: Vec<Vec<bool>>) -> MineLayout {
+        MineLayout { mines }
+    }
+
+    /// Places `mines` mines uniformly at random on a `rows` x `cols` board
+    pub fn random(rows: usize, cols: usize, mines: usize, rng: &mut Seeded) -> MineLayout {
+        assert!(mines <= rows * cols, "Too many mines: {}", mines);
+
+        // Partial Fisher-Yates shuffle of the cell indices
+        let mut cells: Vec<usize> = (0..rows * cols).collect();
+        for i in 0..mines {
+            let j = i + rng.below(cells.len() - i);
+            cells.swap(i, j);
+        }
+
+        let mut layout = vec![vec![false; cols]; rows];
+        for cell in &cells[..mines] {
+            layout[cell / cols][cell % cols] = true;
+        }
+        MineLayout::new(layout)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.mines.len()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.mines.first().map_or(0, |row| row.len())
+    }
+
+    pub fn is_mine(&self, row: Row, col: Col) -> bool {
+        self.mines[row][col]
+    }
+
+    /// Returns the layout where a mine at (`row`, `col`) is moved to the first mine-free cell,
+    /// as classic minesweeper does to keep the first click safe.
+    /// Panics if the layout has no mine-free cell.
+    pub(crate) fn first_click_safe(self, row: Row, col: Col) -> MineLayout {
+        if !self.is_mine(row, col) {
+            return self;
+        }
+        let mut mines = self.mines;
+        let cols = mines[0].len();
+        let free = mines
+            .iter()
+            .flatten()
+            .position(|mine| !mine)
+            .expect("No mine-free cell");
+        mines[free / cols][free % cols] = true;
+        mines[row][col] = false;
+        MineLayout::new(mines)
+    }
+
+    pub fn mines_around(&self, row: Row, col: Col) -> Label {
+        self.neighbours(row, col)
+            .into_iter()
+            .filter(|(r, c)| self.is_mine(*r, *c))
+            .count() as Label
+    }
+
+    /// Returns the cells revealed by clicking the cell at (`row`, `col`): the cell itself and,
+    /// if it has no mines around, the flood fill of its zero region with the numbers bordering it.
+    /// Returns nothing if the cell is a mine.
+    pub fn reveal(&self, row: Row, col: Col) -> Vec<(Row, Col)> {
+        if self.is_mine(row, col) {
+            return vec![];
+        }
+
+        let mut revealed = vec![vec![false; self.cols()]; self.rows()];
+        let mut result = vec![];
+        let mut queue = VecDeque::new();
+        revealed[row][col] = true;
+        queue.push_back((row, col));
+
+        while let Some((r, c)) = queue.pop_front() {
+            result.push((r, c));
+            if self.mines_around(r, c) != 0 {
+                continue;
+            }
+            for (nr, nc) in self.neighbours(r, c) {
+                if !revealed[nr][nc] {
+                    revealed[nr][nc] = true;
+                    queue.push_back((nr, nc));
+                }
+            }
+        }
+
+        result
+    }
+
+    fn neighbours(&self, row: Row, col: Col) -> Vec<(Row, Col)> {
+        Square8.neighbours(row, col, self.rows(), self.cols(), false)
+    }
+
+    /// Builds the visible configuration of the layout.
+    /// Revealed mine-free cells are labeled with the number of mines around,
+    /// revealed mines are shown as mines, everything else stays covered except the `probe`.
+    pub fn configuration(
+        &self,
+        is_revealed: impl Fn(Row, Col) -> bool,
+        probe: (Row, Col),
+    ) -> Configuration {
+        let board = self
+            .mines
+            .iter()
+            .map(|row| vec![Square::Empty; row.len()])
+            .collect();
+        let mut conf = Configuration {
+            board,
+            topology: Arc::new(Square8),
+            wrap: false,
+            anti_mines: false,
+        };
+
+        for row in 0..self.rows() {
+            for col in 0..self.mines[row].len() {
+                conf.board[row][col] = if (row, col) == probe {
+                    Square::Probe
+                } else if !is_revealed(row, col) {
+                    Square::Empty
+                } else if self.is_mine(row, col) {
+                    Square::Mine
+                } else {
+                    let mines = conf
+                        .neighbours(row, col)
+                        .into_iter()
+                        .filter(|(r, c)| self.is_mine(*r, *c))
+                        .count();
+                    Square::Number(mines as Label)
+                };
+            }
+        }
+
+        conf
+    }
+}
@@ -1,23 +1,79 @@
-use datafrog;
 use datafrog::Iteration;
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Instant;
 
+pub mod batch;
+mod budget;
+mod cache;
+pub mod corpus;
+pub mod export;
+#[cfg(feature = "server")]
+pub mod http;
+mod layout;
+mod minimize;
+pub mod model;
+mod opening;
+mod options;
+mod parse;
+pub mod protocol;
+mod random;
+mod search;
+pub mod simulate;
+mod solver;
+mod sparse;
+mod stats;
 #[cfg(test)]
 mod test;
+pub mod topology;
+mod trace;
+mod transform;
+mod validate;
+mod window;
+mod world;
+
+pub use budget::{analyze_with_budget, PartialAnalysis};
+pub use cache::CacheStats;
+pub use layout::MineLayout;
+pub use minimize::minimize;
+pub use opening::{best_opening, compute_3bv, compute_openings, opening_scores};
+pub use options::{CancellationToken, Interruption, SolveOptions, Strategy};
+pub use parse::ParseOptions;
+pub use random::Seeded;
+pub use search::{
+    analyze_with_mine_range, analyze_with_mine_range_with, counterexample, counterexample_with,
+    exact_verdict, exact_verdict_with, exists_safe_move, exists_safe_move_with, solutions,
+    solutions_with, what_if, what_if_with, HypotheticalOutcome, MineRangeAnalysis,
+};
+pub use solver::{EngineKind, Solver, Verdict};
+pub use sparse::SparseConfiguration;
+pub use stats::SolveStats;
+pub use topology::Topology;
+use trace::Recording;
+pub use trace::{iterations, render_trace, trace, DeductionStep, Reason};
+pub use transform::Rect;
+pub use validate::{validate_against, Violation};
+pub use window::{analyze_window, BoundaryPolicy, WindowVerdict};
+pub use world::{InfiniteWorld, Reveal};
 
 type Row = usize;
 type Col = usize;
-type Label = usize;
+type Label = isize;
 
 /// A board cell
 #[derive(Copy, Clone, Debug, Ord, PartialOrd, PartialEq, Eq)]
-enum Square {
+pub enum Square {
     /// Covered cell
     Empty,
 
     /// Mine cell
     Mine,
 
+    /// Anti-mine cell, counts as -1 mine in the anti-mine variant
+    AntiMine,
+
     /// Mine-free cell
     Safe,
 
@@ -29,177 +85,751 @@ enum Square {
 }
 
 impl Square {
-    fn from(s: &str) -> Square {
+    fn parse(s: &str) -> Option<Square> {
         match s {
-            "_" => Square::Empty,
-            "*" => Square::Mine,
-            "s" => Square::Safe,
-            "?" => Square::Probe,
-            _ => match s.parse::<Label>() {
-                Ok(num) if num <= 8 => Square::Number(num),
-                Ok(_) => panic!("Invalid number of mines: {}", s),
-                Err(_) => panic!("Invalid square label: {}", s),
-            },
+            "_" => Some(Square::Empty),
+            "*" => Some(Square::Mine),
+            "!" => Some(Square::AntiMine),
+            "s" => Some(Square::Safe),
+            "?" => Some(Square::Probe),
+            _ => s.parse::<Label>().ok().map(Square::Number),
+        }
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Square::Empty => write!(f, "_"),
+            Square::Mine => write!(f, "*"),
+            Square::AntiMine => write!(f, "!"),
+            Square::Safe => write!(f, "s"),
+            Square::Probe => write!(f, "?"),
+            Square::Number(num) => write!(f, "{}", num),
         }
     }
 }
 
+#[derive(Clone, Debug)]
 pub struct Configuration {
     board: Vec<Vec<Square>>,
+    topology: Arc<dyn Topology>,
+    /// Whether neighbours wrap across the board edges
+    wrap: bool,
+    /// Whether cells may contain anti-mines, so that numbers may be negative
+    anti_mines: bool,
 }
 
 impl Configuration {
+    /// Parses a board configuration.
+    /// The board may be preceded by header lines of the form `<key>: <value>`:
+    /// - `topology: <name>` chooses the board topology, see `topology::from_name`;
+    ///   the classic eight-neighbour one is used by default
+    /// - `wrap: true` makes neighbours wrap across the board edges
+    /// - `anti-mines: true` enables the anti-mine variant
+    ///
+    /// Blank lines separate the layers of a 3D board, which uses the `layers` topology.
+    /// Alternative glyphs are accepted as well, see `ParseOptions`.
     pub fn from(raw_conf: String) -> Configuration {
-        let board: Vec<Vec<_>> = raw_conf
-            .lines()
-            .map(|line| line.split_whitespace().collect::<Vec<_>>())
-            .map(|row| row.iter().map(|square| Square::from(square)).collect())
-            .collect();
+        Configuration::from_with(raw_conf, &ParseOptions::default())
+    }
+
+    /// Parses a board configuration like `from`, reading the squares as `options` say
+    pub fn from_with(raw_conf: String, options: &ParseOptions) -> Configuration {
+        let mut lines = raw_conf.lines().map(|line| line.trim()).peekable();
 
-        Configuration { board }
+        let mut topology: Option<Arc<dyn Topology>> = None;
+        let mut wrap = false;
+        let mut anti_mines = false;
+        while let Some((key, value)) = lines.peek().and_then(|line| Configuration::header(line)) {
+            match key {
+                "topology" => topology = Some(topology::from_name(value)),
+                "wrap" => {
+                    wrap = value
+                        .parse()
+                        .unwrap_or_else(|_| panic!("Invalid wrap option: {}", value))
+                }
+                "anti-mines" => {
+                    anti_mines = value
+                        .parse()
+                        .unwrap_or_else(|_| panic!("Invalid anti-mines option: {}", value))
+                }
+                _ => panic!("Invalid header: {}", key),
+            }
+            lines.next();
+        }
+
+        let mut layers: Vec<Vec<Vec<Square>>> = vec![vec![]];
+        for line in lines {
+            let layer = layers.last_mut().unwrap();
+            if !line.is_empty() {
+                layer.push(
+                    options
+                        .tokens(line)
+                        .into_iter()
+                        .map(|token| options.square(token))
+                        .collect(),
+                );
+            } else if !layer.is_empty() {
+                layers.push(vec![]);
+            }
+        }
+        if layers.len() > 1 && layers.last().is_some_and(|layer| layer.is_empty()) {
+            layers.pop();
+        }
+
+        if layers.len() > 1 {
+            let height = layers[0].len();
+            if layers.iter().any(|layer| layer.len() != height) {
+                panic!("Invalid board: layers have different heights");
+            }
+            match &topology {
+                Some(topology) if topology.layer_height() != Some(height) => panic!(
+                    "Invalid board: layers don't match the topology: {}",
+                    topology.name()
+                ),
+                _ => topology = Some(Arc::new(topology::Layers::new(height))),
+            }
+        }
+
+        let topology = topology.unwrap_or_else(|| Arc::new(topology::Square8));
+        Configuration::new(layers.concat(), topology, wrap, anti_mines)
     }
 
-    fn is_mine(&self, row: Row, col: Col) -> bool {
-        match self.board[row][col] {
-            Square::Mine => true,
-            _ => false,
+    /// Splits a header line into a key and a value
+    fn header(line: &str) -> Option<(&str, &str)> {
+        let separator = line.find(':')?;
+        Some((line[..separator].trim(), line[separator + 1..].trim()))
+    }
+
+    fn new(
+        board: Vec<Vec<Square>>,
+        topology: Arc<dyn Topology>,
+        wrap: bool,
+        anti_mines: bool,
+    ) -> Configuration {
+        let cols = board.first().map_or(0, |row| row.len());
+        if board.iter().any(|row| row.len() != cols) {
+            panic!("Invalid board: rows have different lengths");
+        }
+        if let Some(height) = topology.layer_height() {
+            if !board.len().is_multiple_of(height) {
+                panic!("Invalid board: layers have different heights");
+            }
+        }
+
+        let conf = Configuration {
+            board,
+            topology,
+            wrap,
+            anti_mines,
+        };
+        for (row, squares) in conf.board.iter().enumerate() {
+            for (col, square) in squares.iter().enumerate() {
+                let max = conf.neighbours(row, col).len() as Label;
+                let min = if anti_mines { -max } else { 0 };
+                match square {
+                    Square::Number(n) if *n < min || *n > max => {
+                        panic!("Invalid number of mines: {}", n)
+                    }
+                    Square::AntiMine if !anti_mines => {
+                        panic!("Anti-mines are only allowed in the anti-mine variant")
+                    }
+                    _ => {}
+                }
+            }
         }
+
+        conf
+    }
+
+    /// Returns the same board with neighbours defined by `topology`
+    pub fn with_topology(self, topology: impl Topology + 'static) -> Configuration {
+        Configuration::new(self.board, Arc::new(topology), self.wrap, self.anti_mines)
     }
 
-    fn is_empty(&self, row: Row, col: Col) -> bool {
+    /// Returns the same board where neighbours wrap across the edges if `wrap` is set
+    pub fn with_wrap(self, wrap: bool) -> Configuration {
+        Configuration::new(self.board, self.topology, wrap, self.anti_mines)
+    }
+
+    /// Returns the same board in the anti-mine variant if `anti_mines` is set
+    pub fn with_anti_mines(self, anti_mines: bool) -> Configuration {
+        Configuration::new(self.board, self.topology, self.wrap, anti_mines)
+    }
+
+    /// Returns the same board with the probe moved to the covered cell at (`row`, `col`)
+    pub fn with_probe(mut self, row: Row, col: Col) -> Configuration {
         match self.board[row][col] {
-            Square::Empty => true,
-            Square::Probe => true,
-            _ => false,
+            Square::Empty | Square::Probe => {}
+            square => panic!("Can't probe an uncovered cell: {}", square),
         }
+        for square in self.board.iter_mut().flatten() {
+            if *square == Square::Probe {
+                *square = Square::Empty;
+            }
+        }
+        self.board[row][col] = Square::Probe;
+        self
+    }
+
+    pub fn topology(&self) -> &dyn Topology {
+        self.topology.as_ref()
+    }
+
+    pub fn wrap(&self) -> bool {
+        self.wrap
+    }
+
+    pub fn anti_mines(&self) -> bool {
+        self.anti_mines
+    }
+
+    pub fn rows(&self) -> usize {
+        self.board.len()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.board.first().map_or(0, |row| row.len())
+    }
+
+    fn neighbours(&self, row: Row, col: Col) -> Vec<(Row, Col)> {
+        self.topology
+            .neighbours(row, col, self.rows(), self.cols(), self.wrap)
+    }
+}
+
+/// A board the solver can check, i.e. a set of squares with a neighbour relation.
+/// Cells not listed by `squares` are covered.
+pub trait Board {
+    fn square(&self, row: Row, col: Col) -> Square;
+
+    fn neighbours(&self, row: Row, col: Col) -> Vec<(Row, Col)>;
+
+    /// All squares which are not covered, together with the probe
+    fn squares(&self) -> Vec<(Row, Col, Square)>;
+
+    /// Whether cells may contain anti-mines
+    fn anti_mines(&self) -> bool;
+}
+
+impl Board for Configuration {
+    fn square(&self, row: Row, col: Col) -> Square {
+        self.board[row][col]
     }
 
     fn neighbours(&self, row: Row, col: Col) -> Vec<(Row, Col)> {
+        Configuration::neighbours(self, row, col)
+    }
+
+    fn squares(&self) -> Vec<(Row, Col, Square)> {
         let mut result = vec![];
-        let size = self.board.len();
+        for (i, row) in self.board.iter().enumerate() {
+            let row_squares = row.iter().enumerate().map(|(j, square)| (i, j, *square));
+            result.extend(row_squares.filter(|(_, _, square)| *square != Square::Empty));
+        }
+        result
+    }
+
+    fn anti_mines(&self) -> bool {
+        self.anti_mines
+    }
+}
 
-        // Previous row
-        if row > 1 {
-            let prev_row = row - 1;
-            if col > 1 {
-                result.push((prev_row, col - 1));
+impl PartialEq for Configuration {
+    fn eq(&self, other: &Configuration) -> bool {
+        self.board == other.board
+            && self.topology.name() == other.topology.name()
+            && self.wrap == other.wrap
+            && self.anti_mines == other.anti_mines
+    }
+}
+
+impl Eq for Configuration {}
+
+impl Configuration {
+    /// Writes the board with the glyphs of `options`, so that `from_with` reads it back
+    pub fn render(&self, options: &ParseOptions) -> String {
+        let mut result = String::new();
+        self.write(&mut result, options)
+            .expect("Failed to render a board");
+        result
+    }
+
+    fn write(&self, f: &mut impl fmt::Write, options: &ParseOptions) -> fmt::Result {
+        let name = self.topology.name();
+        if name != topology::Square8.name() {
+            writeln!(f, "topology: {}", name)?;
+        }
+        if self.wrap {
+            writeln!(f, "wrap: true")?;
+        }
+        if self.anti_mines {
+            writeln!(f, "anti-mines: true")?;
+        }
+        let height = self.topology.layer_height();
+        for (i, row) in self.board.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
             }
-            result.push((prev_row, col));
-            if col + 1 < size {
-                result.push((prev_row, col + 1));
+            // an empty line between the layers of a 3D board
+            if i > 0 && height.is_some_and(|height| i.is_multiple_of(height)) {
+                writeln!(f)?;
             }
+            let labels: Vec<String> = row.iter().map(|square| options.glyph(*square)).collect();
+            let separator = if options.unspaced() { "" } else { " " };
+            write!(f, "{}", labels.join(separator))?;
         }
+        Ok(())
+    }
+}
 
-        // This row
-        if col > 1 {
-            result.push((row, col - 1));
-        }
-        if col + 1 < size {
-            result.push((row, col + 1));
+impl fmt::Display for Configuration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, &ParseOptions::default())
+    }
+}
+
+/// Returns the constraint imposed by the number cell at (`row`, `col`) labeled with `n`,
+/// taking into account the squares already `verified` during the iteration.
+/// Returns `None` if there are no covered neighbours left.
+fn constraint(
+    board: &impl Board,
+    row: Row,
+    col: Col,
+    n: Label,
+    verified: &HashMap<(Row, Col), Square>,
+) -> Option<Constraint> {
+    let mut cells = vec![];
+    let mut mines = 0;
+
+    for (r, c) in board.neighbours(row, col) {
+        match verified
+            .get(&(r, c))
+            .copied()
+            .unwrap_or_else(|| board.square(r, c))
+        {
+            Square::Mine => mines += 1,
+            Square::AntiMine => mines -= 1,
+            Square::Empty | Square::Probe => cells.push((r, c)),
+            _ => {}
         }
+    }
 
-        // Next row
-        let next_row = row + 1;
-        if next_row < size {
-            if col > 1 {
-                result.push((next_row, col - 1));
-            }
-            result.push((next_row, col));
-            if col + 1 < size {
-                result.push((next_row, col + 1));
-            }
+    if cells.is_empty() || (!board.anti_mines() && mines > n) {
+        return None;
+    }
+
+    cells.sort();
+    Some(Constraint {
+        cells,
+        mines: n - mines,
+        source: Some(((row, col), n)),
+    })
+}
+
+/// Returns the constraint that the covered `cells` hold exactly `mines` mines, found by comparing
+/// constraints in earlier iterations, without the cells `verified` since.
+/// Returns `None` if there are no covered cells left.
+fn difference(
+    cells: &[(Row, Col)],
+    mut mines: Label,
+    anti_mines: bool,
+    verified: &HashMap<(Row, Col), Square>,
+) -> Option<Constraint> {
+    let mut covered = vec![];
+    for cell in cells {
+        match verified.get(cell) {
+            Some(Square::Mine) => mines -= 1,
+            Some(Square::AntiMine) => mines += 1,
+            Some(_) => {}
+            None => covered.push(*cell),
         }
+    }
 
-        result
+    if covered.is_empty() || (!anti_mines && mines < 0) {
+        return None;
+    }
+
+    Some(Constraint {
+        cells: covered,
+        mines,
+        source: None,
+    })
+}
+
+/// The covered `cells` contain exactly `mines` mines,
+/// counting anti-mines as -1 in the anti-mine variant
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Constraint {
+    /// Sorted covered cells
+    cells: Vec<(Row, Col)>,
+    mines: Label,
+    /// The number cell and its label the constraint comes from,
+    /// `None` for the differences of other constraints
+    source: Option<((Row, Col), Label)>,
+}
+
+impl Constraint {
+    fn is_subset_of(&self, other: &Constraint) -> bool {
+        self.cells
+            .iter()
+            .all(|cell| other.cells.binary_search(cell).is_ok())
+    }
+
+    /// Derives the contents of the `cells` if the constraint is trivial
+    fn derive(
+        cells: &[(Row, Col)],
+        mines: Label,
+        anti_mines: bool,
+        derived: &mut Vec<((Row, Col), Square)>,
+    ) {
+        let len = cells.len() as Label;
+        let square = if mines == len {
+            // All cells are mines if there are as many mines as cells
+            Square::Mine
+        } else if anti_mines && mines == -len {
+            // All cells are anti-mines if there are as many anti-mines as cells
+            Square::AntiMine
+        } else if mines == 0 && (!anti_mines || len == 1) {
+            // All cells are safe if there are no mines left,
+            // unless mines and anti-mines may cancel each other out
+            Square::Safe
+        } else {
+            return;
+        };
+        derived.extend(cells.iter().map(|cell| (*cell, square)));
     }
 }
 
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ProbeResult {
     Safe,
     Unsafe,
     Unknown,
+    /// The solver ran out of time, see `SolveOptions::timeout`
+    TimedOut,
+    /// The solver call was cancelled, see `SolveOptions::cancel`
+    Cancelled,
+}
+
+impl From<Interruption> for ProbeResult {
+    fn from(interruption: Interruption) -> ProbeResult {
+        match interruption {
+            Interruption::TimedOut => ProbeResult::TimedOut,
+            Interruption::Cancelled => ProbeResult::Cancelled,
+        }
+    }
 }
 
 pub fn check_configuration(conf: Configuration) -> ProbeResult {
-    // `bool` means safety of the square
-    let mut verified: HashMap<(Row, Col), bool> = HashMap::new();
+    check_board(&conf)
+}
+
+/// Checks the probe of `conf` with the strategy of `options`, giving up when they say so.
+/// See `Solver::check` to also learn which engine decided.
+pub fn check_configuration_with(conf: Configuration, options: &SolveOptions) -> ProbeResult {
+    Solver::new()
+        .with_options(options.clone())
+        .check(&conf)
+        .result
+}
+
+/// Checks the probe of any `Board`, e.g. a `SparseConfiguration`
+pub fn check_board(board: &impl Board) -> ProbeResult {
+    check_board_with(board, &SolveOptions::default())
+}
+
+/// Checks the probe of any `Board`, giving up when `options` say so
+pub fn check_board_with(board: &impl Board, options: &SolveOptions) -> ProbeResult {
+    check_board_stats(board, options).0
+}
+
+/// Same as `check_board_with`, but also reports what the solver did
+pub fn check_board_stats(board: &impl Board, options: &SolveOptions) -> (ProbeResult, SolveStats) {
+    let mut stats = SolveStats::default();
+
+    let probe = match preflight(board) {
+        Ok(probe) => probe,
+        Err(result) => return (result, stats),
+    };
+
+    let verified = match deduce_stats(board, options, &mut stats) {
+        Ok(verified) => verified,
+        Err(interruption) => return (interruption.into(), stats),
+    };
+
+    let result = match verified.get(&probe) {
+        Some(Square::Safe) => ProbeResult::Safe,
+        Some(_) => ProbeResult::Unsafe,
+        None => ProbeResult::Unknown,
+    };
+    (result, stats)
+}
+
+/// Finds the probe of `board`, i.e. the move to check.
+/// Panics if there is no probe or more than one.
+pub(crate) fn find_probe(board: &impl Board) -> (Row, Col) {
+    let mut probes = board
+        .squares()
+        .into_iter()
+        .filter(|(_, _, square)| *square == Square::Probe)
+        .map(|(row, col, _)| (row, col));
+    let probe = probes.next().expect("No probe provided");
+    if probes.next().is_some() {
+        panic!("Invalid board: more than one probe");
+    }
+    probe
+}
+
+/// Checks `board` before running an engine on it, so that all entry points treat degenerate
+/// boards alike. Returns the probe, or the verdict if it is known without running an engine:
+/// a board without numbers doesn't constrain the probe, which is then `Unknown`.
+pub(crate) fn preflight(board: &impl Board) -> Result<(Row, Col), ProbeResult> {
+    let probe = find_probe(board);
+    let has_numbers = board
+        .squares()
+        .iter()
+        .any(|(_, _, square)| matches!(square, Square::Number(_)));
+    if !has_numbers {
+        return Err(ProbeResult::Unknown);
+    }
+    Ok(probe)
+}
+
+/// Runs the rule engine on `board`, returning the covered cells it could decide
+/// mapped to `Safe`, `Mine` or `AntiMine`
+pub(crate) fn deduce(board: &impl Board) -> HashMap<(Row, Col), Square> {
+    deduce_with(board, &SolveOptions::default()).expect("No limits to interrupt the solver")
+}
+
+/// Same as `deduce`, but stops early if `options` say so
+pub(crate) fn deduce_with(
+    board: &impl Board,
+    options: &SolveOptions,
+) -> Result<HashMap<(Row, Col), Square>, Interruption> {
+    deduce_stats(board, options, &mut SolveStats::default())
+}
+
+/// Same as `deduce_with`, recording what the rule engine did into `stats`
+pub(crate) fn deduce_stats(
+    board: &impl Board,
+    options: &SolveOptions,
+    stats: &mut SolveStats,
+) -> Result<HashMap<(Row, Col), Square>, Interruption> {
+    deduce_traced(board, options, stats, None)
+}
+
+/// Same as `deduce_stats`, also recording every inference and iteration into `recording` if given
+pub(crate) fn deduce_traced(
+    board: &impl Board,
+    options: &SolveOptions,
+    stats: &mut SolveStats,
+    mut recording: Option<&mut Recording>,
+) -> Result<HashMap<(Row, Col), Square>, Interruption> {
+    let start = Instant::now();
+
+    // `Safe`, `Mine` or `AntiMine` squares deduced during the iteration
+    let mut verified: HashMap<(Row, Col), Square> = HashMap::new();
 
     let mut iteration = Iteration::new();
     let squares = iteration.variable::<(Row, Col, Square)>("board");
 
-    // flatten all cells with their indices
-    let mut enumerated_squares: Vec<(Row, Col, Square)> = vec![];
-    for (i, row) in conf.board.iter().enumerate() {
-        let row_squares = row.iter().enumerate().map(|(j, square)| (i, j, *square));
-        enumerated_squares.extend(row_squares);
-    }
-    
-    // find a probe, i.e. a move to check
-    let probe: (Row, Col) = enumerated_squares
+    // all uncovered cells with their indices
+    let enumerated_squares = board.squares();
+
+    // collect all number cells
+    let numbers: Vec<(Row, Col, Label)> = enumerated_squares
         .iter()
-        .find(|(_, _, square)| match square {
-            Square::Probe => true,
-            _ => false,
+        .filter_map(|(row, col, square)| match square {
+            Square::Number(n) => Some((*row, *col, *n)),
+            _ => None,
         })
-        .map(|(i, j, _)| (*i, *j)).expect("No probe provided");
+        .collect();
 
-    // add all board cells into `squares`
+    // add all uncovered cells into `squares`
     squares.extend(enumerated_squares);
 
-    while iteration.changed() {
-        for (row, col, square) in squares.recent.borrow().elements.clone() {
-            let neighbours = conf.neighbours(row, col);
+    // The differences found by the subset rule, which later iterations compare as well,
+    // and whether the last iteration found new ones
+    let mut differences: Vec<(Vec<(Row, Col)>, Label)> = vec![];
+    let mut seen: HashSet<(Vec<(Row, Col)>, Label)> = HashSet::new();
+    let mut found = false;
+
+    stats.setup_time = start.elapsed();
 
-            let neighbours_mines: Vec<(Row, Col)> = neighbours
-                .clone()
-                .into_iter()
-                .filter(|(r, c)| conf.is_mine(*r, *c))
-                .collect();
+    while iteration.changed() || found {
+        if let Some(interruption) = options.interruption(start) {
+            stats.rules_time = start.elapsed() - stats.setup_time;
+            return Err(interruption);
+        }
+
+        let mut constraints: Vec<Constraint> = numbers
+            .iter()
+            .filter_map(|(row, col, n)| constraint(board, *row, *col, *n, &verified))
+            .collect();
+        let total = constraints.len();
+        constraints.extend(
+            differences.iter().filter_map(|(cells, mines)| {
+                difference(cells, *mines, board.anti_mines(), &verified)
+            }),
+        );
 
-            let neighbours_empty: Vec<(Row, Col)> = neighbours
-                .clone()
-                .into_iter()
-                .filter(|(r, c)| conf.is_empty(*r, *c))
-                .collect();
+        // Neighbouring numbers often see the same covered cells,
+        // and duplicates would only repeat the same deductions
+        constraints.sort();
+        constraints.dedup_by(|a, b| a.cells == b.cells && a.mines == b.mines);
+
+        if stats.iterations == 0 {
+            stats.constraints = total;
+            stats.unique_constraints = constraints.len();
+            stats.variables = constraints
+                .iter()
+                .flat_map(|constraint| constraint.cells.iter())
+                .collect::<HashSet<_>>()
+                .len();
+        }
+        stats.iterations += 1;
 
-            if neighbours_empty.is_empty() {
-                continue;
+        if let Some(recording) = recording.as_deref_mut() {
+            // the `squares` relation as seen by this iteration
+            let mut snapshot: Vec<(Row, Col, Square)> = squares.recent.borrow().elements.clone();
+            for relation in squares.stable.borrow().iter() {
+                snapshot.extend(relation.elements.iter().copied());
             }
+            snapshot.sort();
+            recording.snapshots.push(snapshot);
+        }
 
-            match square {
-                // All empty neighbours are safe if `n == neighbours_mines.len()`
-                Square::Number(n) if n == neighbours_mines.len() => {
-                    for (row, col) in neighbours_empty {
-                        verified.insert((row, col), true);
-                    }
+        let mut derived: Vec<((Row, Col), Square)> = vec![];
+
+        for constraint in &constraints {
+            let before = derived.len();
+            Constraint::derive(
+                &constraint.cells,
+                constraint.mines,
+                board.anti_mines(),
+                &mut derived,
+            );
+            if let Some(recording) = recording.as_deref_mut() {
+                let reason = match constraint.source {
+                    Some((cell, label)) => Reason::Number {
+                        cell,
+                        label,
+                        mines: constraint.mines,
+                    },
+                    None => Reason::Difference {
+                        cells: constraint.cells.clone(),
+                        mines: constraint.mines,
+                    },
+                };
+                record(
+                    recording,
+                    stats.iterations,
+                    reason,
+                    &derived,
+                    before,
+                    &verified,
+                );
+            }
+        }
+
+        // If the covered neighbours of `smaller` are a subset of the ones of `larger`,
+        // the difference contains exactly `larger.mines - smaller.mines` mines.
+        // Undecided differences are new constraints for the next iteration.
+        let mut found_differences = vec![];
+        for smaller in &constraints {
+            if let Some(interruption) = options.interruption(start) {
+                stats.rules_time = start.elapsed() - stats.setup_time;
+                return Err(interruption);
+            }
+            for larger in &constraints {
+                if smaller.cells.len() >= larger.cells.len() || !smaller.is_subset_of(larger) {
+                    continue;
                 }
-                // All empty neighbours are unsafe if `n == neighbours_mines.len() + neighbours_empty.len()`
-                Square::Number(n) if n == neighbours_mines.len() + neighbours_empty.len() => {
-                    for (row, col) in neighbours_empty {
-                        verified.insert((row, col), false);
-                    }
+                stats.subset_pairs += 1;
+                let mines = larger.mines - smaller.mines;
+                let difference: Vec<(Row, Col)> = larger
+                    .cells
+                    .iter()
+                    .filter(|cell| smaller.cells.binary_search(cell).is_err())
+                    .cloned()
+                    .collect();
+                let before = derived.len();
+                Constraint::derive(&difference, mines, board.anti_mines(), &mut derived);
+                if derived.len() == before && seen.insert((difference.clone(), mines)) {
+                    found_differences.push((difference.clone(), mines));
+                }
+                if let Some(recording) = recording.as_deref_mut() {
+                    let reason = match (smaller.source, larger.source) {
+                        (Some(smaller), Some(larger)) => Reason::Subset {
+                            smaller,
+                            larger,
+                            mines,
+                        },
+                        _ => Reason::Difference {
+                            cells: difference,
+                            mines,
+                        },
+                    };
+                    record(
+                        recording,
+                        stats.iterations,
+                        reason,
+                        &derived,
+                        before,
+                        &verified,
+                    );
                 }
-                // Uncertain
-                _ => {}
             }
         }
 
         // Update the board
-        squares.from_map(&squares, |(row, col, square)| {
-            match verified.get(&(*row, *col)) {
-                None => (*row, *col, *square),
-                Some(true) => (*row, *col, Square::Safe),
-                Some(false) => (*row, *col, Square::Mine),
+        let mut updated: Vec<(Row, Col, Square)> = vec![];
+        for ((row, col), square) in derived {
+            if verified.insert((row, col), square).is_none() {
+                updated.push((row, col, square));
             }
-        });
+        }
+        stats.deduced = verified.len();
+        squares.extend(updated);
+        found = !found_differences.is_empty();
+        differences.extend(found_differences);
     }
 
     squares.complete();
+    stats.rules_time = start.elapsed() - stats.setup_time;
 
-    match verified.get(&probe) {
-        Some(true) => ProbeResult::Safe,
-        Some(false) => ProbeResult::Unsafe,
-        None => ProbeResult::Unknown,
+    Ok(verified)
+}
+
+/// Records the cells `derived` from `before` on as a step of `recording`,
+/// unless they are already known from earlier steps
+fn record(
+    recording: &mut Recording,
+    iteration: usize,
+    reason: Reason,
+    derived: &[((Row, Col), Square)],
+    before: usize,
+    verified: &HashMap<(Row, Col), Square>,
+) {
+    let known = |cell: &(Row, Col)| {
+        verified.contains_key(cell) || derived[..before].iter().any(|(other, _)| other == cell)
+    };
+    let cells: Vec<(Row, Col)> = derived[before..]
+        .iter()
+        .map(|(cell, _)| *cell)
+        .filter(|cell| !known(cell))
+        .collect();
+    if let Some((_, square)) = derived[before..].first() {
+        if !cells.is_empty() {
+            recording.steps.push(DeductionStep {
+                iteration,
+                reason,
+                cells,
+                square: *square,
+            });
+        }
     }
 }
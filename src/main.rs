@@ -1,7 +1,40 @@
-use std::io::{self, Read};
-use minesweeper::{Configuration, check_configuration, ProbeResult};
+use std::collections::HashMap;
+use std::env;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::net::TcpListener;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+use minesweeper::{Configuration, check_configuration, ProbeResult, SolveOptions, batch, protocol, render_trace, trace, iterations};
 
 fn main() -> io::Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.first().map(|arg| arg.as_str()) {
+        None => check(false, false),
+        Some("--trace") => check(true, false),
+        Some("--trace-iterations") => check(false, true),
+        Some("serve") => serve(args.get(1)),
+        Some("batch") => batch(&args[1..]),
+        Some("bench") => bench(&args[1..]),
+        #[cfg(feature = "server")]
+        Some("http") => http(args.get(1)),
+        Some(arg) => {
+            eprintln!("Unknown command: {}", arg);
+            usage()
+        }
+    }
+}
+
+fn usage() -> ! {
+    let http = if cfg!(feature = "server") { " | http [<address>]" } else { "" };
+    eprintln!("Usage: minesweeper [--trace | --trace-iterations | batch <dir> [--out <file>] [--timeout <ms>] | bench [--seeds <n>] [--timeout <ms>] | serve [<address>]{}]", http);
+    std::process::exit(2);
+}
+
+/// Checks a board read from stdin, printing every inference of the solver if `show_trace` is set
+/// and the board as seen by every iteration if `show_iterations` is set
+fn check(show_trace: bool, show_iterations: bool) -> io::Result<()> {
     println!("A Minesweeper board configuration consists of `_` (unknown), `?` (probe), number (number of mines around).");
     println!("Enter a consistent Minesweeper board configuration with one probe (ending with EOF):");
     let mut buffer = String::new();
@@ -9,12 +42,125 @@ fn main() -> io::Result<()> {
 
     let raw_conf = buffer.trim().to_string();
     let conf = Configuration::from(raw_conf);
+    if show_trace {
+        print!("{}", render_trace(&trace(&conf)));
+    }
+    if show_iterations {
+        for (i, board) in iterations(&conf).iter().enumerate() {
+            println!("Iteration {}:\n{}\n", i + 1, board);
+        }
+    }
     let probe_result = match check_configuration(conf) {
         ProbeResult::Safe => "safe",
         ProbeResult::Unsafe => "unsafe",
-        ProbeResult::Unknown => "unknown"
+        ProbeResult::Unknown => "unknown",
+        ProbeResult::TimedOut => "undecided, the solver timed out",
+        ProbeResult::Cancelled => "undecided, the solver was cancelled"
     };
     println!("The probe is {}", probe_result);
 
     Ok(())
 }
+
+/// Analyzes every board file in a directory, writing CSV to stdout or to the `--out` file,
+/// or JSON if its name ends with `.json`. Each engine gives up on a board after `--timeout`
+/// milliseconds, 10 seconds by default, so that a single hard board doesn't stall the batch.
+fn batch(args: &[String]) -> io::Result<()> {
+    let (dir, flags) = match args.split_first() {
+        Some((dir, flags)) => (dir, parse_flags(flags, &["--out", "--timeout"])),
+        None => usage()
+    };
+    let out = flags.get("--out");
+
+    // Invalid boards are reported in the results, no need to print their panics
+    std::panic::set_hook(Box::new(|_| {}));
+    let records = batch::analyze_dir_with(Path::new(dir), &timeout_options(&flags))?;
+    let disagreements = records.iter().filter(|record| record.disagreement).count();
+    eprintln!("Analyzed {} boards, {} disagreements", records.len(), disagreements);
+
+    match out {
+        None => batch::write_csv(&records, io::stdout().lock()),
+        Some(out) if out.ends_with(".json") => batch::write_json(&records, File::create(out)?),
+        Some(out) => batch::write_csv(&records, File::create(out)?)
+    }
+}
+
+/// Runs both engines over generated beginner, intermediate and expert games and a sweep of mine
+/// densities, one board of each for each of `--seeds` seeds, 10 by default. Writes the verdicts
+/// and timings as CSV to stdout and sums them up by kind of board on stderr.
+fn bench(args: &[String]) -> io::Result<()> {
+    let flags = parse_flags(args, &["--seeds", "--timeout"]);
+    let seeds: u64 = flags.get("--seeds").map_or(10, |seeds| seeds.parse().unwrap_or_else(|_| usage()));
+    let options = timeout_options(&flags);
+
+    let mut records = vec![];
+    // Kinds of boards with their number of boards, total times and disagreements
+    let mut totals: Vec<(String, usize, u128, u128, usize)> = vec![];
+    for seed in 1..=seeds {
+        for (index, (name, conf)) in batch::generated_boards(seed).into_iter().enumerate() {
+            let record = batch::analyze_configuration(format!("{} (seed {})", name, seed), &conf, &options);
+            if totals.len() == index {
+                totals.push((name, 0, 0, 0, 0));
+            }
+            let total = &mut totals[index];
+            total.1 += 1;
+            total.2 += record.rules_us;
+            total.3 += record.search_us;
+            total.4 += usize::from(record.disagreement);
+            records.push(record);
+        }
+    }
+
+    for (name, boards, rules_us, search_us, disagreements) in totals {
+        eprintln!("{}: {} boards, rules {} us, search {} us, {} disagreements", name, boards, rules_us, search_us, disagreements);
+    }
+    batch::write_csv(&records, io::stdout().lock())
+}
+
+/// Reads `--flag value` pairs among `names`, exiting with the usage on anything else
+fn parse_flags<'a>(args: &'a [String], names: &[&str]) -> HashMap<&'a str, &'a String> {
+    if !args.len().is_multiple_of(2) {
+        usage()
+    }
+    args.chunks(2)
+        .map(|flag| if names.contains(&flag[0].as_str()) { (flag[0].as_str(), &flag[1]) } else { usage() })
+        .collect()
+}
+
+/// Options giving up after the `--timeout` milliseconds of `flags`, 10 seconds by default
+fn timeout_options(flags: &HashMap<&str, &String>) -> SolveOptions {
+    let timeout = match flags.get("--timeout") {
+        Some(ms) => Duration::from_millis(ms.parse().unwrap_or_else(|_| usage())),
+        None => Duration::from_secs(10)
+    };
+    SolveOptions::new().with_timeout(timeout)
+}
+
+/// Speaks the JSON protocol on stdin/stdout, or on TCP connections to `address` if given
+fn serve(address: Option<&String>) -> io::Result<()> {
+    // Invalid boards are reported in the responses, no need to print their panics
+    std::panic::set_hook(Box::new(|_| {}));
+    let address = match address {
+        Some(address) => address,
+        None => return protocol::serve(io::stdin().lock(), io::stdout().lock()),
+    };
+
+    let listener = TcpListener::bind(address)?;
+    for stream in listener.incoming() {
+        let stream = stream?;
+        thread::spawn(move || {
+            let reader = BufReader::new(stream.try_clone()?);
+            protocol::serve(reader, stream)
+        });
+    }
+    Ok(())
+}
+
+/// Serves the HTTP API on `address`, `127.0.0.1:8080` by default
+#[cfg(feature = "server")]
+fn http(address: Option<&String>) -> io::Result<()> {
+    // The same as for the protocol
+    std::panic::set_hook(Box::new(|_| {}));
+    let address = address.map_or("127.0.0.1:8080", |address| address.as_str());
+    tokio::runtime::Runtime::new()?.block_on(minesweeper::http::serve(address))
+}
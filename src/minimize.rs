@@ -0,0 +1,67 @@
+use crate::{Configuration, Rect, Square};
+
+/// Greedily shrinks `conf` while `predicate` still holds, producing a minimal repro,
+/// e.g. for a board where the solver returns a wrong verdict.
+/// Rows, or layers of 3D boards, and columns are cropped off the edges and revealed cells
+/// are covered one by one, until no single step preserves the predicate. The probe is always kept.
+pub fn minimize(conf: &Configuration, predicate: impl Fn(&Configuration) -> bool) -> Configuration {
+    assert!(
+        predicate(conf),
+        "The predicate doesn't hold for the initial configuration"
+    );
+
+    let mut current = conf.clone();
+    loop {
+        let before = current.clone();
+
+        while let Some(cropped) = edge_crops(&current).into_iter().find(|c| predicate(c)) {
+            current = cropped;
+        }
+
+        for row in 0..current.rows() {
+            for col in 0..current.cols() {
+                if matches!(current.board[row][col], Square::Empty | Square::Probe) {
+                    continue;
+                }
+                let mut blanked = current.clone();
+                blanked.board[row][col] = Square::Empty;
+                if predicate(&blanked) {
+                    current = blanked;
+                }
+            }
+        }
+
+        if current == before {
+            return current;
+        }
+    }
+}
+
+/// Returns the boards with one row or column cropped off an edge, keeping the probe.
+/// 3D boards lose a whole layer instead of a row.
+fn edge_crops(conf: &Configuration) -> Vec<Configuration> {
+    let (rows, cols) = (conf.rows(), conf.cols());
+    let height = conf.topology().layer_height().unwrap_or(1);
+    let probe = conf.board.iter().enumerate().find_map(|(row, squares)| {
+        squares
+            .iter()
+            .position(|square| *square == Square::Probe)
+            .map(|col| (row, col))
+    });
+
+    let mut rects = vec![];
+    if rows > height {
+        rects.push(Rect::new(height, 0, rows - height, cols));
+        rects.push(Rect::new(0, 0, rows - height, cols));
+    }
+    if cols > 1 {
+        rects.push(Rect::new(0, 1, rows, cols - 1));
+        rects.push(Rect::new(0, 0, rows, cols - 1));
+    }
+
+    rects
+        .into_iter()
+        .filter(|rect| probe.is_none_or(|(row, col)| rect.contains(row, col)))
+        .map(|rect| conf.crop(rect))
+        .collect()
+}
@@ -0,0 +1,141 @@
+//! Exports the constraints of a board as pseudo-Boolean (OPB) and MiniZinc models,
+//! so that hard instances can be tried on PB and CP solvers.
+//!
+//! Every covered cell is a 0-1 variable numbered from 1 in row-major order, and every number
+//! requires its covered neighbours to hold the mines it doesn't see yet. Numbers without covered
+//! neighbours are left out if they see all their mines, and become constraints that can't be
+//! satisfied otherwise, so that inconsistent boards stay unsatisfiable. Optionally the covered
+//! cells hold exactly the mines of the board not shown as `*`.
+
+use crate::{Col, Configuration, Label, Row, Square};
+
+/// Writes `conf` in the OPB format of the pseudo-Boolean competitions
+pub fn to_opb(conf: &Configuration, mines: Option<usize>) -> String {
+    let model = Model::new(conf, mines);
+
+    // A constraint needs a term, so constraints without variables weigh x1 by 0
+    let unsatisfiable = model
+        .constraints
+        .iter()
+        .any(|(variables, _)| variables.is_empty());
+    let variables = if unsatisfiable {
+        model.cells.len().max(1)
+    } else {
+        model.cells.len()
+    };
+
+    let mut result = format!(
+        "* #variable= {} #constraint= {}\n",
+        variables,
+        model.constraints.len()
+    );
+    result += &model.comments("* ");
+    for (variables, sum) in &model.constraints {
+        let terms: Vec<String> = if variables.is_empty() {
+            vec![String::from("0 x1")]
+        } else {
+            variables.iter().map(|v| format!("+1 x{}", v)).collect()
+        };
+        result += &format!("{} = {} ;\n", terms.join(" "), sum);
+    }
+    result
+}
+
+/// Writes `conf` as a MiniZinc model
+pub fn to_minizinc(conf: &Configuration, mines: Option<usize>) -> String {
+    let model = Model::new(conf, mines);
+
+    let mut result = model.comments("% ");
+    result += &format!("array[1..{}] of var 0..1: x;\n", model.cells.len());
+    for (variables, sum) in &model.constraints {
+        if variables.is_empty() {
+            result += "constraint false;\n";
+            continue;
+        }
+        let terms: Vec<String> = variables.iter().map(|v| format!("x[{}]", v)).collect();
+        result += &format!("constraint {} = {};\n", terms.join(" + "), sum);
+    }
+    result += "solve satisfy;\n";
+    result
+}
+
+/// The variables and linear constraints of a board
+struct Model {
+    /// Covered cells, the variable of a cell is its index plus one
+    cells: Vec<(Row, Col)>,
+    probe: Option<usize>,
+    /// Variables summing up to a number of mines, no variables for a number that can't be met
+    constraints: Vec<(Vec<usize>, Label)>,
+}
+
+impl Model {
+    fn new(conf: &Configuration, mines: Option<usize>) -> Model {
+        assert!(!conf.anti_mines(), "Anti-mines are not supported");
+
+        let mut cells = vec![];
+        let mut probe = None;
+        let mut known_mines = 0;
+        for (row, squares) in conf.board.iter().enumerate() {
+            for (col, square) in squares.iter().enumerate() {
+                match square {
+                    Square::Probe => {
+                        probe = Some(cells.len() + 1);
+                        cells.push((row, col));
+                    }
+                    Square::Empty => cells.push((row, col)),
+                    Square::Mine => known_mines += 1,
+                    _ => {}
+                }
+            }
+        }
+
+        let mut constraints = vec![];
+        for (row, squares) in conf.board.iter().enumerate() {
+            for (col, square) in squares.iter().enumerate() {
+                if let Square::Number(n) = square {
+                    let mut variables = vec![];
+                    let mut sum = *n;
+                    for (r, c) in conf.neighbours(row, col) {
+                        match conf.board[r][c] {
+                            Square::Mine => sum -= 1,
+                            Square::Empty | Square::Probe => {
+                                variables.push(cells.binary_search(&(r, c)).unwrap() + 1)
+                            }
+                            _ => {}
+                        }
+                    }
+                    variables.sort();
+                    if !variables.is_empty() || sum != 0 {
+                        constraints.push((variables, sum));
+                    }
+                }
+            }
+        }
+
+        if let Some(mines) = mines {
+            let sum = mines as Label - known_mines;
+            if !cells.is_empty() || sum != 0 {
+                constraints.push(((1..=cells.len()).collect(), sum));
+            }
+        }
+
+        Model {
+            cells,
+            probe,
+            constraints,
+        }
+    }
+
+    /// Lists the cells of the variables in comment lines starting with `prefix`
+    fn comments(&self, prefix: &str) -> String {
+        let mut result = String::new();
+        for (i, (row, col)) in self.cells.iter().enumerate() {
+            result += &format!("{}x{} is the cell ({},{})", prefix, i + 1, row, col);
+            if self.probe == Some(i + 1) {
+                result += ", the probe";
+            }
+            result += "\n";
+        }
+        result
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{Col, MineLayout, Row, Seeded};
+
+/// Estimates the expected number of cells revealed by the first click on every cell
+/// of a `rows` x `cols` board with `mines` mines, by sampling `samples` random layouts.
+/// As in `simulate`, the first click is safe: a mine under it is moved to the first
+/// mine-free cell before revealing. The moved mine lands next to a click on the top left corner,
+/// which therefore scores lower than the other corners, e.g. 40/9 against 47/9 on a 3x3 board
+/// with one mine.
+pub fn opening_scores(
+    rows: usize,
+    cols: usize,
+    mines: usize,
+    samples: usize,
+    rng: &mut Seeded,
+) -> Vec<Vec<f64>> {
+    assert!(samples > 0, "No samples to estimate openings from");
+    assert!(mines < rows * cols, "Too many mines: {}", mines);
+
+    let mut totals = vec![vec![0; cols]; rows];
+    for _ in 0..samples {
+        let layout = MineLayout::random(rows, cols, mines, rng);
+        for (row, sizes) in opening_sizes(&layout).into_iter().enumerate() {
+            for (col, size) in sizes.into_iter().enumerate() {
+                totals[row][col] += match size {
+                    Some(size) => size,
+                    None => {
+                        let relocated = layout.clone().first_click_safe(row, col);
+                        relocated.reveal(row, col).len()
+                    }
+                };
+            }
+        }
+    }
+
+    totals
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|total| total as f64 / samples as f64)
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the first click revealing the most cells on average, see `opening_scores`
+pub fn best_opening(
+    rows: usize,
+    cols: usize,
+    mines: usize,
+    samples: usize,
+    rng: &mut Seeded,
+) -> (Row, Col) {
+    let scores = opening_scores(rows, cols, mines, samples, rng);
+    let mut best = (0, 0);
+    for (row, scores_row) in scores.iter().enumerate() {
+        for (col, score) in scores_row.iter().enumerate() {
+            if *score > scores[best.0][best.1] {
+                best = (row, col);
+            }
+        }
+    }
+    best
+}
+
+/// Returns the openings of `layout`, i.e. the areas revealed by clicking a cell without mines
+/// around: a zero region together with the numbers bordering it. The cells of every opening
+/// are sorted, and the openings are ordered by their first cell.
+pub fn compute_openings(layout: &MineLayout) -> Vec<Vec<(Row, Col)>> {
+    let mut flooded = vec![vec![false; layout.cols()]; layout.rows()];
+    let mut openings = vec![];
+    for row in 0..layout.rows() {
+        for col in 0..layout.cols() {
+            if flooded[row][col] || layout.is_mine(row, col) || layout.mines_around(row, col) != 0 {
+                continue;
+            }
+            let mut opening = layout.reveal(row, col);
+            for &(r, c) in &opening {
+                if layout.mines_around(r, c) == 0 {
+                    flooded[r][c] = true;
+                }
+            }
+            opening.sort();
+            openings.push(opening);
+        }
+    }
+    openings
+}
+
+/// Returns the 3BV of `layout`, the standard difficulty metric: the minimal number of clicks
+/// to clear the board, i.e. one per opening and one per number outside the openings
+pub fn compute_3bv(layout: &MineLayout) -> usize {
+    let openings = compute_openings(layout);
+    let mut opened = vec![vec![false; layout.cols()]; layout.rows()];
+    for &(row, col) in openings.iter().flatten() {
+        opened[row][col] = true;
+    }
+
+    let isolated = (0..layout.rows())
+        .flat_map(|row| (0..layout.cols()).map(move |col| (row, col)))
+        .filter(|&(row, col)| !layout.is_mine(row, col) && !opened[row][col])
+        .count();
+    openings.len() + isolated
+}
+
+/// Returns the number of cells revealed by clicking every cell of `layout`, `None` for mines.
+/// All cells of a zero region open the same area, so it is flooded only once.
+fn opening_sizes(layout: &MineLayout) -> Vec<Vec<Option<usize>>> {
+    let mut sizes = vec![vec![None; layout.cols()]; layout.rows()];
+    for row in 0..layout.rows() {
+        for col in 0..layout.cols() {
+            if sizes[row][col].is_some() || layout.is_mine(row, col) {
+                continue;
+            }
+            if layout.mines_around(row, col) != 0 {
+                sizes[row][col] = Some(1);
+            } else {
+                let opening = layout.reveal(row, col);
+                for &(r, c) in &opening {
+                    if layout.mines_around(r, c) == 0 {
+                        sizes[r][c] = Some(opening.len());
+                    }
+                }
+            }
+        }
+    }
+    sizes
+}
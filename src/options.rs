@@ -0,0 +1,100 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A flag shared between a solver call and the code that may want to abort it,
+/// e.g. a UI thread when the user changes the board
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Asks the solver calls holding this token to stop as soon as possible
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Which engines decide the probe of a `Configuration`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Strategy {
+    /// Only the rule engine, which is fast but may answer `Unknown` on decidable boards
+    #[default]
+    RulesOnly,
+    /// Only the exhaustive search of `exact_verdict`, which is complete but may take
+    /// exponential time. Boards with anti-mines, which the search doesn't support,
+    /// are checked by the rules.
+    SearchOnly,
+    /// The rule engine first, falling back to the search if the probe is still unknown
+    /// and there are at most `frontier_limit` covered cells next to numbers
+    Hybrid { frontier_limit: usize },
+}
+
+/// Limits of a solver call. The rule engine checks them between its iterations,
+/// the search before every cell it assigns.
+#[derive(Clone, Debug, Default)]
+pub struct SolveOptions {
+    pub timeout: Option<Duration>,
+    pub cancel: Option<CancellationToken>,
+    /// Only used by `check_configuration_with`, other boards are checked by the rules
+    pub strategy: Strategy,
+}
+
+impl SolveOptions {
+    pub fn new() -> SolveOptions {
+        SolveOptions::default()
+    }
+
+    /// Returns the same options giving up after `timeout`
+    pub fn with_timeout(self, timeout: Duration) -> SolveOptions {
+        SolveOptions {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Returns the same options giving up once `cancel` is cancelled
+    pub fn with_cancel(self, cancel: CancellationToken) -> SolveOptions {
+        SolveOptions {
+            cancel: Some(cancel),
+            ..self
+        }
+    }
+
+    /// Returns the same options deciding the probe with `strategy`
+    pub fn with_strategy(self, strategy: Strategy) -> SolveOptions {
+        SolveOptions { strategy, ..self }
+    }
+
+    /// Returns the reason to stop a solver call started at `start`, if any
+    pub(crate) fn interruption(&self, start: Instant) -> Option<Interruption> {
+        if self
+            .cancel
+            .as_ref()
+            .is_some_and(|cancel| cancel.is_cancelled())
+        {
+            Some(Interruption::Cancelled)
+        } else if self
+            .timeout
+            .is_some_and(|timeout| start.elapsed() >= timeout)
+        {
+            Some(Interruption::TimedOut)
+        } else {
+            None
+        }
+    }
+}
+
+/// Why a solver call stopped before finishing, see `SolveOptions`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Interruption {
+    TimedOut,
+    Cancelled,
+}
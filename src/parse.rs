@@ -0,0 +1,106 @@
+//! Alternative notations for squares, so that boards pasted from other tools parse as they are.
+
+use crate::Square;
+
+/// Glyphs accepted for squares besides the usual ones, always understood when parsing
+const ALIASES: &[(&str, Square)] = &[
+    (".", Square::Empty),
+    ("□", Square::Empty),
+    ("•", Square::Mine),
+    ("X", Square::Mine),
+];
+
+/// Marks that turn a digit into an emoji keycap, e.g. `1️⃣`
+const KEYCAP: [char; 2] = ['\u{FE0F}', '\u{20E3}'];
+
+/// How `Configuration::from_with` reads squares and `Configuration::render` writes them.
+/// Besides the usual glyphs, `.` and `□` are read as covered cells, `•` and `X` as mines,
+/// and full-width characters and emoji digits as their ASCII counterparts.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Glyphs both read and written for squares, the first one of a square is written
+    glyphs: Vec<(String, Square)>,
+    /// Whether the squares of a row are written without spaces, and read one glyph each
+    unspaced: bool,
+}
+
+impl ParseOptions {
+    pub fn new() -> ParseOptions {
+        ParseOptions::default()
+    }
+
+    /// Returns the same options reading `glyph` as `square` and writing `square` as `glyph`
+    pub fn with_glyph(mut self, glyph: &str, square: Square) -> ParseOptions {
+        self.glyphs.push((glyph.to_string(), square));
+        self
+    }
+
+    /// Returns the same options for rows without spaces between squares if `unspaced` is set,
+    /// e.g. rows of emoji. Every glyph is then a square, so numbers must be single digits.
+    pub fn with_unspaced(self, unspaced: bool) -> ParseOptions {
+        ParseOptions { unspaced, ..self }
+    }
+
+    pub(crate) fn unspaced(&self) -> bool {
+        self.unspaced
+    }
+
+    /// Splits a row into the glyphs of its squares
+    pub(crate) fn tokens<'a>(&self, line: &'a str) -> Vec<&'a str> {
+        if !self.unspaced {
+            return line.split_whitespace().collect();
+        }
+
+        // A glyph is a character together with the keycap marks following it
+        let mut tokens = vec![];
+        let mut start = None;
+        for (i, c) in line.char_indices() {
+            if KEYCAP.contains(&c) {
+                continue;
+            }
+            if let Some(start) = start {
+                tokens.push(&line[start..i]);
+            }
+            start = if c.is_whitespace() { None } else { Some(i) };
+        }
+        if let Some(start) = start {
+            tokens.push(&line[start..]);
+        }
+        tokens
+    }
+
+    /// Reads a square, panicking on unknown glyphs
+    pub(crate) fn square(&self, token: &str) -> Square {
+        if let Some((_, square)) = self.glyphs.iter().find(|(glyph, _)| glyph == token) {
+            return *square;
+        }
+
+        let normalized = normalize(token);
+        match ALIASES.iter().find(|(glyph, _)| *glyph == normalized) {
+            Some((_, square)) => *square,
+            None => Square::parse(&normalized)
+                .unwrap_or_else(|| panic!("Invalid square label: {}", token)),
+        }
+    }
+
+    /// Writes a square
+    pub(crate) fn glyph(&self, square: Square) -> String {
+        match self.glyphs.iter().find(|(_, other)| *other == square) {
+            Some((glyph, _)) => glyph.clone(),
+            None => square.to_string(),
+        }
+    }
+}
+
+/// Replaces full-width characters with their ASCII counterparts and drops keycap marks
+fn normalize(token: &str) -> String {
+    token
+        .chars()
+        .filter(|c| !KEYCAP.contains(c))
+        .map(|c| match c {
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap(),
+            '\u{2212}' => '-',
+            c => c,
+        })
+        .collect()
+}
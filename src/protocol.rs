@@ -0,0 +1,172 @@
+//! A line-delimited JSON protocol, so that frontends can drive the solver as a child process
+//! or over TCP instead of paying the startup cost per query.
+//!
+//! Every request is a JSON object on its own line, e.g.
+//! `{"cmd": "analyze", "board": "1 1\n_ ?", "timeout_ms": 100}`, and gets a response line
+//! `{"ok": true, "result": {...}}` or `{"ok": false, "error": "..."}`. An analysis running out of
+//! time is still a result, with a `timed_out` probe and the statistics up to then.
+
+use crate::{
+    deduce_stats, preflight, Configuration, ProbeResult, SolveOptions, SolveStats, Square,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+enum Request {
+    /// Runs the solver on a board in the text format, for at most `timeout_ms` milliseconds
+    Analyze {
+        board: String,
+        timeout_ms: Option<u64>,
+    },
+}
+
+#[derive(Debug, Serialize)]
+struct Response {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Analysis>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Analysis {
+    /// Verdict for the probe, if the board has one, or `timed_out` on any board if the solver
+    /// ran out of time
+    probe: Option<ProbeResult>,
+    /// Covered cells the solver could decide, none if it ran out of time
+    cells: Vec<Cell>,
+    stats: Stats,
+    time_us: u128,
+}
+
+/// The counters of `SolveStats`, e.g. for showing what the solver did in a UI
+#[derive(Debug, Serialize)]
+struct Stats {
+    variables: usize,
+    constraints: usize,
+    iterations: usize,
+    subset_pairs: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct Cell {
+    row: usize,
+    col: usize,
+    verdict: ProbeResult,
+}
+
+/// Answers requests read from `input` line by line until it ends
+pub fn serve(input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        writeln!(output, "{}", handle(&line))?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+/// Answers a single request line
+pub fn handle(line: &str) -> String {
+    let response = match serde_json::from_str::<Request>(line) {
+        Ok(Request::Analyze { board, timeout_ms }) => match analyze(board, timeout_ms) {
+            Ok(analysis) => Response {
+                ok: true,
+                result: Some(analysis),
+                error: None,
+            },
+            Err(error) => Response {
+                ok: false,
+                result: None,
+                error: Some(error),
+            },
+        },
+        Err(error) => Response {
+            ok: false,
+            result: None,
+            error: Some(format!("Invalid request: {}", error)),
+        },
+    };
+    serde_json::to_string(&response).expect("Failed to serialize a response")
+}
+
+/// Runs the solver on a board in the text format for at most `timeout_ms` milliseconds,
+/// reporting invalid boards as errors
+pub(crate) fn analyze(board: String, timeout_ms: Option<u64>) -> Result<Analysis, String> {
+    let start = Instant::now();
+
+    // The parser panics on invalid boards, which must not bring the server down
+    let conf = catch_panic(|| Configuration::from(board))?;
+    // The same checks as the other entry points, except that the probe is optional here.
+    // The verdict of a board without numbers is `Unknown`, which the rules find as well.
+    if conf
+        .board
+        .iter()
+        .flatten()
+        .any(|square| *square == Square::Probe)
+    {
+        let _ = catch_panic(|| preflight(&conf))?;
+    }
+    let mut options = SolveOptions::default();
+    if let Some(timeout_ms) = timeout_ms {
+        options = options.with_timeout(Duration::from_millis(timeout_ms));
+    }
+    let mut stats = SolveStats::default();
+    let deduced = deduce_stats(&conf, &options, &mut stats);
+
+    let mut probe = None;
+    let mut cells = vec![];
+    let verified = match deduced {
+        Ok(verified) => verified,
+        Err(interruption) => {
+            probe = Some(interruption.into());
+            HashMap::new()
+        }
+    };
+    for (row, squares) in conf.board.iter().enumerate() {
+        for (col, square) in squares.iter().enumerate() {
+            let verdict = match verified.get(&(row, col)) {
+                Some(Square::Safe) => ProbeResult::Safe,
+                Some(_) => ProbeResult::Unsafe,
+                None => ProbeResult::Unknown,
+            };
+            if *square == Square::Probe && probe.is_none() {
+                probe = Some(verdict);
+            }
+            if verdict != ProbeResult::Unknown {
+                cells.push(Cell { row, col, verdict });
+            }
+        }
+    }
+
+    Ok(Analysis {
+        probe,
+        cells,
+        stats: Stats {
+            variables: stats.variables,
+            constraints: stats.constraints,
+            iterations: stats.iterations,
+            subset_pairs: stats.subset_pairs,
+        },
+        time_us: start.elapsed().as_micros(),
+    })
+}
+
+/// Runs `f`, turning a panic into an error with the panic message
+pub(crate) fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|error| {
+        error
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| error.downcast_ref::<&str>().map(|s| s.to_string()))
+            .unwrap_or_else(|| String::from("Invalid board"))
+    })
+}
@@ -0,0 +1,49 @@
+/// A reproducible source of randomness which remembers its seed,
+/// so that randomized results can be reported and replayed by the seed alone
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Seeded {
+    seed: u64,
+    state: u64,
+}
+
+impl Seeded {
+    pub fn new(seed: u64) -> Seeded {
+        Seeded { seed, state: seed }
+    }
+
+    /// The seed the generator was created with
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        splitmix64(&mut self.state)
+    }
+
+    /// A uniformly distributed number in `0..n`
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "Empty range");
+        // Rejection sampling avoids the modulo bias
+        let zone = u64::MAX - u64::MAX % n as u64;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return (value % n as u64) as usize;
+            }
+        }
+    }
+
+    /// `true` with probability `p`
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+}
+
+/// The SplitMix64 generator, see https://prng.di.unimi.it/splitmix64.c
+pub(crate) fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
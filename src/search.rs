@@ -0,0 +1,618 @@
+use crate::options::Interruption;
+use crate::{
+    deduce_with, find_probe, preflight, Col, Configuration, Label, MineLayout, ProbeResult, Row,
+    SolveOptions, Square,
+};
+use std::cell::Cell;
+use std::collections::BTreeSet;
+use std::rc::Rc;
+use std::time::Instant;
+
+/// Finds a mine layout consistent with `conf` that has a mine under the probe,
+/// i.e. a world where clicking the probe loses. Returns `None` if the probe is safe.
+/// Covered cells away from the numbers are left mine-free.
+pub fn counterexample(conf: &Configuration) -> Option<MineLayout> {
+    counterexample_with(conf, &SolveOptions::default()).expect("No limits to interrupt the search")
+}
+
+/// Same as `counterexample`, but gives up when `options` say so
+pub fn counterexample_with(
+    conf: &Configuration,
+    options: &SolveOptions,
+) -> Result<Option<MineLayout>, Interruption> {
+    let search = Search::new(conf).with_limits(options, Instant::now());
+    let layout = example(conf, true, &search);
+    match search.interrupted.get() {
+        Some(interruption) => Err(interruption),
+        None => Ok(layout),
+    }
+}
+
+/// Decides the probe of `conf` by searching for layouts with and without a mine under it.
+/// Unlike the rule engine this is complete, but takes exponential time in the worst case.
+/// Returns `None` if no layout is consistent with `conf`.
+pub fn exact_verdict(conf: &Configuration) -> Option<ProbeResult> {
+    exact_verdict_with(conf, &SolveOptions::default()).expect("No limits to interrupt the search")
+}
+
+/// Same as `exact_verdict`, but gives up when `options` say so
+pub fn exact_verdict_with(
+    conf: &Configuration,
+    options: &SolveOptions,
+) -> Result<Option<ProbeResult>, Interruption> {
+    exact_verdict_since(conf, options, Instant::now())
+}
+
+/// Same as `exact_verdict_with` for a call started at `start`, e.g. after running the rules
+pub(crate) fn exact_verdict_since(
+    conf: &Configuration,
+    options: &SolveOptions,
+    start: Instant,
+) -> Result<Option<ProbeResult>, Interruption> {
+    if let Err(result) = preflight(conf) {
+        return Ok(Some(result));
+    }
+    let search = Search::new(conf).with_limits(options, start);
+    let without_mine = example(conf, false, &search);
+    let with_mine = example(conf, true, &search);
+    if let Some(interruption) = search.interrupted.get() {
+        return Err(interruption);
+    }
+    Ok(match (without_mine, with_mine) {
+        (Some(_), Some(_)) => Some(ProbeResult::Unknown),
+        (Some(_), None) => Some(ProbeResult::Safe),
+        (None, Some(_)) => Some(ProbeResult::Unsafe),
+        (None, None) => None,
+    })
+}
+
+/// Counts the covered cells next to numbers, which bound the time of the search
+pub(crate) fn frontier_size(conf: &Configuration) -> usize {
+    frontier(conf).len()
+}
+
+/// Returns the covered cells next to numbers, with the number of numbers around each of them
+pub(crate) fn frontier(conf: &Configuration) -> Vec<((Row, Col), usize)> {
+    let search = Search::new(conf);
+    search
+        .cells
+        .iter()
+        .zip(&search.watches)
+        .filter(|(_, watches)| !watches.is_empty())
+        .map(|(cell, watches)| (*cell, watches.len()))
+        .collect()
+}
+
+/// Finds a mine layout consistent with `conf` with or without a mine under the probe,
+/// using the `search` of `conf`
+fn example(conf: &Configuration, probe_mine: bool, search: &Search) -> Option<MineLayout> {
+    assert!(!conf.anti_mines(), "Anti-mines are not supported");
+
+    let probe = find_probe(conf);
+
+    if search.has_conflicts() {
+        return None;
+    }
+    let index = search.cells.iter().position(|cell| *cell == probe)?;
+    let mines = search.find(index, probe_mine)?;
+    Some(search.layout(&mines))
+}
+
+/// Returns a covered cell of `conf` which is safe in every consistent layout, if there is one,
+/// i.e. whether the player can avoid guessing. The probe is treated as any other covered cell.
+/// Cells away from the numbers are never provably safe, as they may always hold a mine.
+pub fn exists_safe_move(conf: &Configuration) -> Option<(Row, Col)> {
+    exists_safe_move_with(conf, &SolveOptions::default())
+        .expect("No limits to interrupt the search")
+}
+
+/// Same as `exists_safe_move`, but gives up when `options` say so
+pub fn exists_safe_move_with(
+    conf: &Configuration,
+    options: &SolveOptions,
+) -> Result<Option<(Row, Col)>, Interruption> {
+    let start = Instant::now();
+    // The rule engine is fast and finds most safe cells
+    if let Some(cell) = deduce_with(conf, options)?
+        .into_iter()
+        .filter(|(_, square)| *square == Square::Safe)
+        .map(|(cell, _)| cell)
+        .min()
+    {
+        return Ok(Some(cell));
+    }
+
+    assert!(!conf.anti_mines(), "Anti-mines are not supported");
+    let search = Search::new(conf).with_limits(options, start);
+    if search.has_conflicts() {
+        return Ok(None);
+    }
+
+    // Every layout found shows several cells that may be mines at once,
+    // so only the remaining ones have to be checked one by one
+    let mut maybe_mine = vec![false; search.cells.len()];
+    for cell in 0..search.cells.len() {
+        if maybe_mine[cell] || search.watches[cell].is_empty() {
+            continue;
+        }
+        match search.find(cell, true) {
+            Some(mines) => {
+                for (other, mine) in mines.iter().enumerate() {
+                    maybe_mine[other] |= *mine == Some(true);
+                }
+            }
+            None => {
+                if let Some(interruption) = search.interrupted.get() {
+                    return Err(interruption);
+                }
+                return Ok(Some(search.cells[cell]));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Lazily enumerates every mine layout consistent with `conf`.
+/// The number of layouts grows exponentially with the covered cells away from the numbers.
+pub fn solutions(conf: &Configuration) -> impl Iterator<Item = MineLayout> + '_ {
+    solutions_with(conf, &SolveOptions::default())
+        .map(|layout| layout.expect("No limits to interrupt the search"))
+}
+
+/// Same as `solutions`, but gives up when `options` say so, ending with the `Interruption`
+pub fn solutions_with<'a>(
+    conf: &'a Configuration,
+    options: &SolveOptions,
+) -> impl Iterator<Item = Result<MineLayout, Interruption>> + 'a {
+    assert!(!conf.anti_mines(), "Anti-mines are not supported");
+
+    let search = Search::new(conf).with_limits(options, Instant::now());
+    // Assign the cells next to numbers first, so that conflicts are found early
+    let mut order: Vec<usize> = (0..search.cells.len()).collect();
+    order.sort_by_key(|&cell| search.watches[cell].is_empty());
+
+    Solutions {
+        done: search.has_conflicts(),
+        mines: vec![None; search.cells.len()],
+        position: 0,
+        order,
+        search,
+    }
+}
+
+/// What revealing a covered cell may show, see `what_if`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HypotheticalOutcome {
+    /// The cell holds a mine in some consistent layout, so revealing it may lose
+    pub may_explode: bool,
+    /// The numbers the cell may show if it is safe, in increasing order
+    pub numbers: Vec<Label>,
+}
+
+/// Tells what revealing the covered cell at (`row`, `col`) of `conf` may show, without changing
+/// the board, e.g. for hover previews. Both are empty for an inconsistent board.
+/// Covered cells away from the numbers may hold any number of mines.
+/// Runs several searches, so interactive callers should rather limit them with `what_if_with`.
+pub fn what_if(conf: &Configuration, row: Row, col: Col) -> HypotheticalOutcome {
+    what_if_with(conf, row, col, &SolveOptions::default())
+        .expect("No limits to interrupt the search")
+}
+
+/// Same as `what_if`, but gives up when `options` say so
+pub fn what_if_with(
+    conf: &Configuration,
+    row: Row,
+    col: Col,
+    options: &SolveOptions,
+) -> Result<HypotheticalOutcome, Interruption> {
+    assert!(!conf.anti_mines(), "Anti-mines are not supported");
+    match conf.board[row][col] {
+        Square::Empty | Square::Probe => {}
+        square => panic!("Can't reveal an uncovered cell: {}", square),
+    }
+
+    let search = Search::new(conf).with_limits(options, Instant::now());
+    if search.has_conflicts() {
+        return Ok(HypotheticalOutcome {
+            may_explode: false,
+            numbers: vec![],
+        });
+    }
+    let index = search.cells.binary_search(&(row, col)).unwrap();
+    let may_explode = search.find(index, true).is_some();
+
+    // The mines around the cell are the known ones, the ones next to numbers,
+    // which the search decides, and any number of the others
+    let mut known: Label = 0;
+    let mut frontier = vec![];
+    let mut free: Label = 0;
+    for (r, c) in conf.neighbours(row, col) {
+        match conf.board[r][c] {
+            Square::Mine => known += 1,
+            Square::Empty | Square::Probe if (r, c) != (row, col) => {
+                let neighbour = search.cells.binary_search(&(r, c)).unwrap();
+                if search.watches[neighbour].is_empty() {
+                    free += 1;
+                } else {
+                    frontier.push(neighbour);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut numbers = BTreeSet::new();
+    for mines in 0..=frontier.len() as Label {
+        if search
+            .with_count(&frontier, mines)
+            .find(index, false)
+            .is_some()
+        {
+            numbers.extend(known + mines..=known + mines + free);
+        }
+    }
+
+    if let Some(interruption) = search.interrupted.get() {
+        return Err(interruption);
+    }
+    Ok(HypotheticalOutcome {
+        may_explode,
+        numbers: numbers.into_iter().collect(),
+    })
+}
+
+/// The verdicts of `analyze_with_mine_range`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MineRangeAnalysis {
+    /// Verdicts of all covered cells, sorted by position
+    pub cells: Vec<(Row, Col, ProbeResult)>,
+    /// Covered cells only decided thanks to the range, i.e. `Unknown` without it
+    pub decided_by_range: Vec<(Row, Col)>,
+}
+
+/// Decides every covered cell of `conf` knowing that the board holds between `lo` and `hi` mines,
+/// counting the mines already shown. Unlike the other verdicts of the search, cells away from
+/// the numbers may be decided, e.g. as safe when the numbers need all the mines.
+/// Returns `None` if no layout consistent with `conf` has that many mines.
+pub fn analyze_with_mine_range(
+    conf: &Configuration,
+    lo: usize,
+    hi: usize,
+) -> Option<MineRangeAnalysis> {
+    analyze_with_mine_range_with(conf, lo, hi, &SolveOptions::default())
+        .expect("No limits to interrupt the search")
+}
+
+/// Same as `analyze_with_mine_range`, but gives up when `options` say so
+pub fn analyze_with_mine_range_with(
+    conf: &Configuration,
+    lo: usize,
+    hi: usize,
+    options: &SolveOptions,
+) -> Result<Option<MineRangeAnalysis>, Interruption> {
+    assert!(!conf.anti_mines(), "Anti-mines are not supported");
+    if lo > hi {
+        panic!("Invalid mine range: {}..={}", lo, hi);
+    }
+
+    let search = Search::new(conf).with_limits(options, Instant::now());
+    if search.has_conflicts() {
+        return Ok(None);
+    }
+    let known = conf
+        .board
+        .iter()
+        .flatten()
+        .filter(|square| **square == Square::Mine)
+        .count();
+    if hi < known || (search.cells.is_empty() && lo > known) {
+        return Ok(None);
+    }
+    // The mines left for the covered cells
+    let (lo, hi) = (lo.saturating_sub(known), hi - known);
+
+    let mut analysis = MineRangeAnalysis {
+        cells: vec![],
+        decided_by_range: vec![],
+    };
+    for (index, &(row, col)) in search.cells.iter().enumerate() {
+        let verdict = |lo, hi| match (
+            search.exists_with_count(index, false, lo, hi),
+            search.exists_with_count(index, true, lo, hi),
+        ) {
+            (true, true) => Some(ProbeResult::Unknown),
+            (true, false) => Some(ProbeResult::Safe),
+            (false, true) => Some(ProbeResult::Unsafe),
+            (false, false) => None,
+        };
+        let in_range = verdict(lo, hi);
+        let unbounded = match in_range {
+            Some(ProbeResult::Unknown) | None => None,
+            Some(_) => verdict(0, search.cells.len()),
+        };
+        // An interrupted search finds nothing, which doesn't make the range impossible
+        if let Some(interruption) = search.interrupted.get() {
+            return Err(interruption);
+        }
+        let in_range = match in_range {
+            Some(in_range) => in_range,
+            None => return Ok(None),
+        };
+        if unbounded == Some(ProbeResult::Unknown) {
+            analysis.decided_by_range.push((row, col));
+        }
+        analysis.cells.push((row, col, in_range));
+    }
+    Ok(Some(analysis))
+}
+
+/// Iterative backtracking over the covered cells, see `solutions`
+struct Solutions<'a> {
+    search: Search<'a>,
+    /// The order to assign the cells in
+    order: Vec<usize>,
+    mines: Vec<Option<bool>>,
+    /// The position in `order` of the cell being assigned
+    position: usize,
+    done: bool,
+}
+
+impl Solutions<'_> {
+    /// Returns to the last assigned cell to try its next value
+    fn backtrack(&mut self) {
+        if self.position == 0 {
+            self.done = true;
+        } else {
+            self.position -= 1;
+        }
+    }
+}
+
+impl Iterator for Solutions<'_> {
+    type Item = Result<MineLayout, Interruption>;
+
+    fn next(&mut self) -> Option<Result<MineLayout, Interruption>> {
+        while !self.done {
+            if let Some(interruption) = self.search.interruption() {
+                self.done = true;
+                return Some(Err(interruption));
+            }
+            if self.position == self.order.len() {
+                let layout = self.search.layout(&self.mines);
+                self.backtrack();
+                return Some(Ok(layout));
+            }
+
+            let cell = self.order[self.position];
+            self.mines[cell] = match self.mines[cell] {
+                None => Some(false),
+                Some(false) => Some(true),
+                Some(true) => None,
+            };
+            if self.mines[cell].is_none() {
+                self.backtrack();
+            } else if self.search.is_consistent(&self.mines, cell) {
+                self.position += 1;
+            }
+        }
+        None
+    }
+}
+
+/// The covered cells of a configuration and the numbers constraining them
+#[derive(Clone)]
+struct Search<'a> {
+    conf: &'a Configuration,
+    /// Covered cells which may hold a mine
+    cells: Vec<(Row, Col)>,
+    /// For every number, the indices of its covered neighbours and the mines among them
+    constraints: Vec<(Vec<usize>, Label)>,
+    /// For every cell, the constraints it appears in
+    watches: Vec<Vec<usize>>,
+    /// The limits of the call running the search and its start, if any
+    limits: Option<(SolveOptions, Instant)>,
+    /// Why the search stopped early, after which it finds nothing.
+    /// Shared with the copies of `with_count`.
+    interrupted: Rc<Cell<Option<Interruption>>>,
+}
+
+impl<'a> Search<'a> {
+    fn new(conf: &'a Configuration) -> Search<'a> {
+        let mut cells = vec![];
+        for (row, squares) in conf.board.iter().enumerate() {
+            for (col, square) in squares.iter().enumerate() {
+                if matches!(square, Square::Empty | Square::Probe) {
+                    cells.push((row, col));
+                }
+            }
+        }
+
+        let mut constraints = vec![];
+        let mut watches = vec![vec![]; cells.len()];
+        for (row, squares) in conf.board.iter().enumerate() {
+            for (col, square) in squares.iter().enumerate() {
+                if let Square::Number(n) = square {
+                    let mut covered = vec![];
+                    let mut mines = *n;
+                    for (r, c) in conf.neighbours(row, col) {
+                        match conf.board[r][c] {
+                            Square::Mine => mines -= 1,
+                            Square::Empty | Square::Probe => {
+                                let index = cells.binary_search(&(r, c)).unwrap();
+                                watches[index].push(constraints.len());
+                                covered.push(index);
+                            }
+                            _ => {}
+                        }
+                    }
+                    constraints.push((covered, mines));
+                }
+            }
+        }
+
+        Search {
+            conf,
+            cells,
+            constraints,
+            watches,
+            limits: None,
+            interrupted: Rc::new(Cell::new(None)),
+        }
+    }
+
+    /// Returns the same search giving up when `options` say so for a call started at `start`
+    fn with_limits(self, options: &SolveOptions, start: Instant) -> Search<'a> {
+        Search {
+            limits: Some((options.clone(), start)),
+            ..self
+        }
+    }
+
+    /// Finds an assignment of the cells next to numbers with `cell` set to `mine`,
+    /// leaving the other cells unassigned
+    fn find(&self, cell: usize, mine: bool) -> Option<Vec<Option<bool>>> {
+        let mut mines = vec![None; self.cells.len()];
+        mines[cell] = Some(mine);
+        if !self.is_consistent(&mines, cell) {
+            return None;
+        }
+
+        // Only the cells next to numbers matter, the others may stay mine-free. Cells of different
+        // components share no numbers, so each component is assigned on its own and a conflict
+        // in one doesn't enumerate the others again.
+        let mut components = self.components();
+        components.sort_by_key(|component| !component.contains(&cell));
+        for component in components {
+            let order: Vec<usize> = component
+                .into_iter()
+                .filter(|&other| other != cell)
+                .collect();
+            if !self.extend(&mut mines, &order) {
+                return None;
+            }
+        }
+        Some(mines)
+    }
+
+    /// Splits the cells next to numbers into components linked by shared numbers.
+    /// The cells of a component are listed as they are reached from its first cell,
+    /// so that cells sharing numbers are assigned close to each other.
+    fn components(&self) -> Vec<Vec<usize>> {
+        let mut reached = vec![false; self.cells.len()];
+        let mut components = vec![];
+        for first in 0..self.cells.len() {
+            if reached[first] || self.watches[first].is_empty() {
+                continue;
+            }
+            reached[first] = true;
+            let mut component = vec![first];
+            let mut next = 0;
+            while let Some(&cell) = component.get(next) {
+                next += 1;
+                for &constraint in &self.watches[cell] {
+                    for &other in &self.constraints[constraint].0 {
+                        if !reached[other] {
+                            reached[other] = true;
+                            component.push(other);
+                        }
+                    }
+                }
+            }
+            components.push(component);
+        }
+        components
+    }
+
+    /// Returns the same search with `cells` holding exactly `mines` mines
+    fn with_count(&self, cells: &[usize], mines: Label) -> Search<'a> {
+        let mut search = self.clone();
+        for &cell in cells {
+            search.watches[cell].push(search.constraints.len());
+        }
+        search.constraints.push((cells.to_vec(), mines));
+        search
+    }
+
+    /// Checks whether some assignment with `cell` set to `mine` places between `lo` and `hi`
+    /// mines on the covered cells. The cells away from the numbers may hold any of the mines.
+    fn exists_with_count(&self, cell: usize, mine: bool, lo: usize, hi: usize) -> bool {
+        let frontier: Vec<usize> = (0..self.cells.len())
+            .filter(|&other| !self.watches[other].is_empty())
+            .collect();
+        let on_frontier = !self.watches[cell].is_empty();
+        // The cells away from the numbers other than `cell`
+        let free = self.cells.len() - frontier.len() - usize::from(!on_frontier);
+        let forced = usize::from(!on_frontier && mine);
+
+        (0..=frontier.len()).any(|mines| {
+            let least = mines + forced;
+            least <= hi
+                && least + free >= lo
+                && self
+                    .with_count(&frontier, mines as Label)
+                    .find(cell, mine)
+                    .is_some()
+        })
+    }
+
+    /// Checks for numbers without covered neighbours which don't match their mines
+    fn has_conflicts(&self) -> bool {
+        self.constraints
+            .iter()
+            .any(|(cells, mines)| cells.is_empty() && *mines != 0)
+    }
+
+    /// Checks that the constraints of `cell` can still be satisfied
+    fn is_consistent(&self, mines: &[Option<bool>], cell: usize) -> bool {
+        self.watches[cell].iter().all(|&constraint| {
+            let (cells, expected) = &self.constraints[constraint];
+            let placed = cells.iter().filter(|&&c| mines[c] == Some(true)).count() as Label;
+            let open = cells.iter().filter(|&&c| mines[c].is_none()).count() as Label;
+            placed <= *expected && placed + open >= *expected
+        })
+    }
+
+    /// Checks the limits of the search, remembering the first reason to stop
+    fn interruption(&self) -> Option<Interruption> {
+        if let Some((options, start)) = &self.limits {
+            if self.interrupted.get().is_none() {
+                self.interrupted.set(options.interruption(*start));
+            }
+        }
+        self.interrupted.get()
+    }
+
+    /// Assigns the `order`ed cells by backtracking, returning `false` if there is no solution
+    fn extend(&self, mines: &mut [Option<bool>], order: &[usize]) -> bool {
+        if self.interruption().is_some() {
+            return false;
+        }
+
+        let (cell, rest) = match order.split_first() {
+            Some((cell, rest)) => (*cell, rest),
+            None => return true,
+        };
+        for value in [false, true] {
+            mines[cell] = Some(value);
+            if self.is_consistent(mines, cell) && self.extend(mines, rest) {
+                return true;
+            }
+        }
+        mines[cell] = None;
+        false
+    }
+
+    /// Builds the layout with the assigned mines and the mines already on the board
+    fn layout(&self, mines: &[Option<bool>]) -> MineLayout {
+        let mut layout: Vec<Vec<bool>> = self
+            .conf
+            .board
+            .iter()
+            .map(|squares| squares.iter().map(|s| *s == Square::Mine).collect())
+            .collect();
+        for (index, &(row, col)) in self.cells.iter().enumerate() {
+            layout[row][col] = mines[index] == Some(true);
+        }
+        MineLayout::new(layout)
+    }
+}
@@ -0,0 +1,255 @@
+//! Plays whole games on random layouts, to measure how changes to the solver affect play.
+
+use crate::{deduce, exists_safe_move, Col, Configuration, MineLayout, Row, Seeded, Square};
+use std::time::{Duration, Instant};
+
+/// A player choosing moves from what is visible on the board, e.g. a heuristic or learned agent
+pub trait Strategy {
+    /// Called before every game, so that strategies can forget the previous one
+    fn new_game(&mut self) {}
+
+    /// Chooses the next move. It must reveal a covered cell.
+    fn choose_move(&mut self, view: &BoardView) -> Move;
+}
+
+/// A move of a `Strategy`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Move {
+    /// Reveals a cell known to be safe
+    Reveal(Row, Col),
+    /// Reveals a cell that may be a mine, counted in `SimulationReport::guesses`
+    Guess(Row, Col),
+}
+
+/// The board as seen by the player during a game
+#[derive(Clone, Debug)]
+pub struct BoardView {
+    conf: Configuration,
+}
+
+impl BoardView {
+    /// The revealed cells as numbers and the covered ones as `Empty`, without a probe.
+    /// Use `Configuration::with_probe` to check a cell.
+    pub fn configuration(&self) -> &Configuration {
+        &self.conf
+    }
+
+    pub fn rows(&self) -> usize {
+        self.conf.rows()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.conf.cols()
+    }
+
+    pub fn is_covered(&self, row: Row, col: Col) -> bool {
+        self.conf.board[row][col] == Square::Empty
+    }
+
+    pub fn covered(&self) -> Vec<(Row, Col)> {
+        (0..self.rows())
+            .flat_map(|row| (0..self.cols()).map(move |col| (row, col)))
+            .filter(|&(row, col)| self.is_covered(row, col))
+            .collect()
+    }
+}
+
+/// The built-in bot: reveals every cell the solver or the exhaustive search proves safe,
+/// and guesses a random covered cell that isn't a known mine when there are none
+#[derive(Clone, Debug)]
+pub struct SolverBot {
+    rng: Seeded,
+    /// Cells proven safe but not revealed yet
+    safe: Vec<(Row, Col)>,
+}
+
+impl SolverBot {
+    pub fn new(seed: u64) -> SolverBot {
+        SolverBot {
+            rng: Seeded::new(seed),
+            safe: vec![],
+        }
+    }
+}
+
+impl Strategy for SolverBot {
+    fn new_game(&mut self) {
+        self.safe.clear();
+    }
+
+    fn choose_move(&mut self, view: &BoardView) -> Move {
+        // Safe cells may have been revealed by the flood fill in the meantime
+        self.safe.retain(|&(row, col)| view.is_covered(row, col));
+        if self.safe.is_empty() {
+            let verified = deduce(view.configuration());
+            self.safe = view
+                .covered()
+                .into_iter()
+                .filter(|cell| verified.get(cell) == Some(&Square::Safe))
+                .rev()
+                .collect();
+
+            // The rules may miss safe cells the exhaustive search finds
+            if self.safe.is_empty() {
+                self.safe.extend(exists_safe_move(view.configuration()));
+            }
+
+            if self.safe.is_empty() {
+                let candidates: Vec<(Row, Col)> = view
+                    .covered()
+                    .into_iter()
+                    .filter(|cell| verified.get(cell) != Some(&Square::Mine))
+                    .collect();
+                let (row, col) = candidates[self.rng.below(candidates.len())];
+                return Move::Guess(row, col);
+            }
+        }
+
+        let (row, col) = self.safe.pop().unwrap();
+        Move::Reveal(row, col)
+    }
+}
+
+/// Size and mine count of the simulated boards
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BoardParams {
+    pub rows: usize,
+    pub cols: usize,
+    pub mines: usize,
+}
+
+impl BoardParams {
+    pub fn beginner() -> BoardParams {
+        BoardParams {
+            rows: 9,
+            cols: 9,
+            mines: 10,
+        }
+    }
+
+    pub fn intermediate() -> BoardParams {
+        BoardParams {
+            rows: 16,
+            cols: 16,
+            mines: 40,
+        }
+    }
+
+    pub fn expert() -> BoardParams {
+        BoardParams {
+            rows: 16,
+            cols: 30,
+            mines: 99,
+        }
+    }
+}
+
+/// The outcome of `simulate`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SimulationReport {
+    pub games: usize,
+    pub wins: usize,
+    /// Moves made without a cell proven safe, over all games
+    pub guesses: usize,
+    pub time: Duration,
+}
+
+impl SimulationReport {
+    /// The share of games won, 0 if no games were played
+    pub fn win_rate(&self) -> f64 {
+        self.per_game(self.wins)
+    }
+
+    /// The guesses per game, 0 if no games were played
+    pub fn average_guesses(&self) -> f64 {
+        self.per_game(self.guesses)
+    }
+
+    fn per_game(&self, total: usize) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            total as f64 / self.games as f64
+        }
+    }
+}
+
+/// Plays `games` games on random boards described by `params`, choosing the moves by `strategy`.
+/// The first click is in the top left corner and never hits a mine.
+/// The same `seed` always generates the same boards.
+pub fn simulate(
+    strategy: &mut dyn Strategy,
+    params: BoardParams,
+    games: usize,
+    seed: u64,
+) -> SimulationReport {
+    assert!(
+        params.mines < params.rows * params.cols,
+        "Too many mines: {}",
+        params.mines
+    );
+
+    let start = Instant::now();
+    let mut rng = Seeded::new(seed);
+    let mut report = SimulationReport {
+        games,
+        ..SimulationReport::default()
+    };
+
+    for _ in 0..games {
+        let layout = MineLayout::random(params.rows, params.cols, params.mines, &mut rng)
+            .first_click_safe(0, 0);
+        strategy.new_game();
+        let (won, guesses) = play(&layout, (0, 0), strategy);
+        if won {
+            report.wins += 1;
+        }
+        report.guesses += guesses;
+    }
+
+    report.time = start.elapsed();
+    report
+}
+
+/// Plays a game starting at `first`, returning whether it was won and the number of guesses
+fn play(layout: &MineLayout, first: (Row, Col), strategy: &mut dyn Strategy) -> (bool, usize) {
+    let mines = (0..layout.rows())
+        .flat_map(|row| (0..layout.cols()).map(move |col| (row, col)))
+        .filter(|&(row, col)| layout.is_mine(row, col))
+        .count();
+    let mut revealed = vec![vec![false; layout.cols()]; layout.rows()];
+    let mut guesses = 0;
+    let mut next = Move::Reveal(first.0, first.1);
+
+    loop {
+        let (row, col) = match next {
+            Move::Reveal(row, col) => (row, col),
+            Move::Guess(row, col) => {
+                guesses += 1;
+                (row, col)
+            }
+        };
+        if revealed[row][col] {
+            panic!("Invalid move: ({}, {}) is already revealed", row, col);
+        }
+        if layout.is_mine(row, col) {
+            return (false, guesses);
+        }
+        for (r, c) in layout.reveal(row, col) {
+            revealed[r][c] = true;
+        }
+
+        let covered = revealed.iter().flatten().filter(|r| !**r).count();
+        if covered == mines {
+            return (true, guesses);
+        }
+
+        let probe = (0..layout.rows())
+            .flat_map(|row| (0..layout.cols()).map(move |col| (row, col)))
+            .find(|&(row, col)| !revealed[row][col])
+            .unwrap();
+        let mut conf = layout.configuration(|row, col| revealed[row][col], probe);
+        conf.board[probe.0][probe.1] = Square::Empty;
+        next = strategy.choose_move(&BoardView { conf });
+    }
+}
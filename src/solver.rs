@@ -0,0 +1,158 @@
+use crate::cache::{canonical_key, PatternCache};
+use crate::{
+    check_board_stats, search, CacheStats, Configuration, ProbeResult, SolveOptions, SolveStats,
+    Strategy,
+};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// The engine that decided a `Verdict`
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EngineKind {
+    /// The rule engine
+    Rules,
+    /// The exhaustive search of `exact_verdict`
+    Search,
+}
+
+/// The outcome of `Solver::check`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Verdict {
+    pub result: ProbeResult,
+    pub engine: EngineKind,
+    /// What the rule engine did, left empty if only the search ran
+    pub stats: SolveStats,
+}
+
+/// Checks probes with both engines as chosen by `SolveOptions::strategy`,
+/// so that callers don't need to know which one answers.
+/// A solver can be shared between threads, e.g. a UI thread and a background analysis,
+/// and its clones share the cache.
+#[derive(Clone, Debug, Default)]
+pub struct Solver {
+    options: SolveOptions,
+    cache: Option<Arc<Mutex<PatternCache>>>,
+}
+
+impl Solver {
+    pub fn new() -> Solver {
+        Solver::default()
+    }
+
+    /// Returns the same solver using `options` for every check, forgetting the cached verdicts
+    pub fn with_options(self, options: SolveOptions) -> Solver {
+        let capacity = self.cache.map(|cache| cache.lock().unwrap().capacity());
+        Solver {
+            options,
+            cache: capacity.map(|capacity| Arc::new(Mutex::new(PatternCache::new(capacity)))),
+        }
+    }
+
+    /// Returns the same solver remembering the verdicts of up to `capacity` patterns.
+    /// Boards whose constraints only differ by a translation, rotation or reflection
+    /// share a verdict, which saves re-solving them but assumes consistent boards.
+    /// A verdict from the cache comes without the statistics of the rule engine.
+    pub fn with_cache(self, capacity: usize) -> Solver {
+        Solver {
+            cache: Some(Arc::new(Mutex::new(PatternCache::new(capacity)))),
+            ..self
+        }
+    }
+
+    /// How well the cache performed so far, if there is one
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache
+            .as_ref()
+            .map(|cache| cache.lock().unwrap().stats())
+    }
+
+    pub fn options(&self) -> &SolveOptions {
+        &self.options
+    }
+
+    /// Checks the probe of `conf`
+    pub fn check(&self, conf: &Configuration) -> Verdict {
+        let search = self.may_search(conf);
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return self.solve(conf, search),
+        };
+
+        let key = canonical_key(conf, search);
+        // The cache isn't locked while solving, so that other threads can use it meanwhile
+        let cached = cache.lock().unwrap().get(&key);
+        if let Some((result, engine)) = cached {
+            return Verdict {
+                result,
+                engine,
+                stats: SolveStats::default(),
+            };
+        }
+        let verdict = self.solve(conf, search);
+        // Interrupted checks may succeed the next time
+        if !matches!(
+            verdict.result,
+            ProbeResult::TimedOut | ProbeResult::Cancelled
+        ) {
+            cache
+                .lock()
+                .unwrap()
+                .insert(key, verdict.result, verdict.engine);
+        }
+        verdict
+    }
+
+    /// Whether the strategy lets the search decide the probe of `conf` if the rules can't
+    fn may_search(&self, conf: &Configuration) -> bool {
+        // The search doesn't support anti-mines
+        !conf.anti_mines()
+            && match self.options.strategy {
+                Strategy::RulesOnly => false,
+                Strategy::SearchOnly => true,
+                Strategy::Hybrid { frontier_limit } => {
+                    search::frontier_size(conf) <= frontier_limit
+                }
+            }
+    }
+
+    /// Checks the probe of `conf`, running the search only if `search` allows it
+    fn solve(&self, conf: &Configuration, search: bool) -> Verdict {
+        let start = Instant::now();
+        match self.options.strategy {
+            Strategy::SearchOnly if search => self.search(conf, SolveStats::default(), start),
+            _ => {
+                let verdict = self.rules(conf);
+                if search && verdict.result == ProbeResult::Unknown {
+                    self.search(conf, verdict.stats, start)
+                } else {
+                    verdict
+                }
+            }
+        }
+    }
+
+    fn rules(&self, conf: &Configuration) -> Verdict {
+        let (result, stats) = check_board_stats(conf, &self.options);
+        Verdict {
+            result,
+            engine: EngineKind::Rules,
+            stats,
+        }
+    }
+
+    /// Runs the search for a call started at `start`, so that it shares the limits with the rules
+    fn search(&self, conf: &Configuration, stats: SolveStats, start: Instant) -> Verdict {
+        let result = match search::exact_verdict_since(conf, &self.options, start) {
+            Ok(verdict) => verdict,
+            Err(interruption) => Some(interruption.into()),
+        };
+        Verdict {
+            // An inconsistent board has no layouts to decide the probe
+            result: result.unwrap_or(ProbeResult::Unknown),
+            engine: EngineKind::Search,
+            stats,
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::topology::Square8;
+use crate::transform::{self, Rect};
+use crate::{Board, Col, Configuration, Row, Square, Topology};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A board storing only its uncovered cells, for huge boards which are mostly covered
+#[derive(Clone, Debug)]
+pub struct SparseConfiguration {
+    rows: usize,
+    cols: usize,
+    squares: HashMap<(Row, Col), Square>,
+    topology: Arc<dyn Topology>,
+    /// Whether neighbours wrap across the board edges
+    wrap: bool,
+}
+
+impl SparseConfiguration {
+    /// Creates a covered `rows` x `cols` board with the classic eight-neighbour topology
+    pub fn new(rows: usize, cols: usize) -> SparseConfiguration {
+        SparseConfiguration {
+            rows,
+            cols,
+            squares: HashMap::new(),
+            topology: Arc::new(Square8),
+            wrap: false,
+        }
+    }
+
+    /// Returns the same board with neighbours defined by `topology`
+    pub fn with_topology(self, topology: impl Topology + 'static) -> SparseConfiguration {
+        SparseConfiguration {
+            topology: Arc::new(topology),
+            ..self
+        }
+    }
+
+    /// Returns the same board where neighbours wrap across the edges if `wrap` is set
+    pub fn with_wrap(self, wrap: bool) -> SparseConfiguration {
+        SparseConfiguration { wrap, ..self }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, row: Row, col: Col) -> Square {
+        self.squares
+            .get(&(row, col))
+            .copied()
+            .unwrap_or(Square::Empty)
+    }
+
+    pub fn set(&mut self, row: Row, col: Col, square: Square) {
+        assert!(
+            row < self.rows && col < self.cols,
+            "Cell ({}, {}) is out of the board",
+            row,
+            col
+        );
+        match square {
+            Square::Empty => self.squares.remove(&(row, col)),
+            _ => self.squares.insert((row, col), square),
+        };
+    }
+
+    /// Returns the part of the board inside `rect`, clamped to the board, as a dense board.
+    /// A number whose neighbours are not the same on the window is replaced with a safe cell.
+    pub fn window(&self, rect: Rect) -> Configuration {
+        let rows = self.rows.min(rect.row + rect.rows).saturating_sub(rect.row);
+        let cols = self.cols.min(rect.col + rect.cols).saturating_sub(rect.col);
+        transform::window(
+            self,
+            Rect::new(rect.row, rect.col, rows, cols),
+            self.topology.clone(),
+            false,
+        )
+    }
+}
+
+impl From<&Configuration> for SparseConfiguration {
+    fn from(conf: &Configuration) -> SparseConfiguration {
+        assert!(
+            !conf.anti_mines,
+            "Sparse boards don't support the anti-mine variant"
+        );
+        let mut sparse = SparseConfiguration {
+            topology: conf.topology.clone(),
+            wrap: conf.wrap,
+            ..SparseConfiguration::new(conf.rows(), conf.cols())
+        };
+        for (row, col, square) in conf.squares() {
+            sparse.set(row, col, square);
+        }
+        sparse
+    }
+}
+
+impl Board for SparseConfiguration {
+    fn square(&self, row: Row, col: Col) -> Square {
+        self.get(row, col)
+    }
+
+    fn neighbours(&self, row: Row, col: Col) -> Vec<(Row, Col)> {
+        self.topology
+            .neighbours(row, col, self.rows, self.cols, self.wrap)
+    }
+
+    fn squares(&self) -> Vec<(Row, Col, Square)> {
+        self.squares
+            .iter()
+            .map(|((row, col), square)| (*row, *col, *square))
+            .collect()
+    }
+
+    fn anti_mines(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+/// What the rule engine did during a solver call
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SolveStats {
+    /// Covered cells that appear in at least one constraint, i.e. the unknowns
+    pub variables: usize,
+    /// Constraints built from the number cells in the first iteration
+    pub constraints: usize,
+    /// Constraints left after removing the duplicates
+    pub unique_constraints: usize,
+    /// Rounds of the rule engine until the fixpoint (or the interruption)
+    pub iterations: usize,
+    /// Pairs of constraints the subset rule was applied to
+    pub subset_pairs: usize,
+    /// Cells decided as `Safe`, `Mine` or `AntiMine`
+    pub deduced: usize,
+    /// Time spent collecting the number cells
+    pub setup_time: Duration,
+    /// Time spent applying the rules
+    pub rules_time: Duration,
+}
+
+impl SolveStats {
+    pub fn total_time(&self) -> Duration {
+        self.setup_time + self.rules_time
+    }
+}
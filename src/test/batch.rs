@@ -0,0 +1,93 @@
+use crate::batch::{
+    analyze, analyze_configuration, analyze_dir, analyze_with, generated_boards, write_csv,
+};
+use crate::{ProbeResult, SolveOptions};
+use std::fs;
+use std::time::Duration;
+
+#[test]
+fn test_analyze() {
+    let record = analyze("safe".to_string(), "1 1 1\n_ _ ?\n".to_string());
+    assert_eq!(record.rules, Some(ProbeResult::Safe));
+    assert_eq!(record.search, Some(ProbeResult::Safe));
+    assert!(!record.disagreement);
+    assert_eq!(record.error, None);
+
+    let record = analyze("invalid".to_string(), "x ?".to_string());
+    assert_eq!(record.rules, None);
+    assert_eq!(record.error, Some("Invalid square label: x".to_string()));
+
+    let record = analyze("inconsistent".to_string(), "* 0\n_ ?".to_string());
+    assert_eq!(record.search, None);
+    assert_eq!(record.error, Some("Inconsistent board".to_string()));
+}
+
+#[test]
+fn test_timeout() {
+    let options = SolveOptions::new().with_timeout(Duration::ZERO);
+    let record = analyze_with("slow".to_string(), "1 1 1\n_ _ ?".to_string(), &options);
+    assert_eq!(record.rules, Some(ProbeResult::TimedOut));
+    assert_eq!(record.search, Some(ProbeResult::TimedOut));
+    assert!(!record.disagreement);
+    assert_eq!(record.error, None);
+}
+
+#[test]
+fn test_analyze_dir() {
+    let dir = std::env::temp_dir().join(format!("minesweeper-batch-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("b.txt"), "_ ?").unwrap();
+    fs::write(dir.join("a.txt"), "1 1 1\n_ _ ?").unwrap();
+    let records = analyze_dir(&dir).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    let files: Vec<&str> = records.iter().map(|record| record.file.as_str()).collect();
+    assert_eq!(files, vec!["a.txt", "b.txt"]);
+    assert_eq!(records[1].rules, Some(ProbeResult::Unknown));
+    assert_eq!(records[1].search, Some(ProbeResult::Unknown));
+
+    let mut records = records;
+    records[1].error = Some("a, \"b\"".to_string());
+    records[0].rules_us = 1;
+    records[0].search_us = 2;
+    records[1].rules_us = 3;
+    records[1].search_us = 4;
+    let mut output = vec![];
+    write_csv(&records, &mut output).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "file,rules,search,rules_us,search_us,disagreement,error\n\
+         a.txt,safe,safe,1,2,false,\n\
+         b.txt,unknown,unknown,3,4,false,\"a, \"\"b\"\"\"\n"
+    );
+}
+
+#[test]
+fn test_generated_boards() {
+    let boards = generated_boards(1);
+    let names: Vec<&str> = boards.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "beginner",
+            "intermediate",
+            "expert",
+            "density-10",
+            "density-15",
+            "density-20",
+            "density-25",
+            "density-30"
+        ]
+    );
+    assert_eq!(
+        generated_boards(1)[2].1.to_string(),
+        boards[2].1.to_string()
+    );
+
+    for (name, conf) in boards {
+        let record = analyze_configuration(name, &conf, &SolveOptions::default());
+        assert!(record.search.is_some(), "{:?}", record);
+        assert!(!record.disagreement, "{:?}", record);
+        assert_eq!(record.error, None);
+    }
+}
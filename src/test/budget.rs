@@ -0,0 +1,43 @@
+use super::corpus_board;
+use crate::{analyze_with_budget, Configuration, PartialAnalysis, ProbeResult};
+use std::time::Duration;
+
+fn conf() -> Configuration {
+    corpus_board("beyond_the_rules")
+}
+
+#[test]
+fn test_full_budget() {
+    let analysis = analyze_with_budget(&conf(), Duration::from_secs(60));
+    assert!(analysis.is_complete());
+    assert_eq!(
+        analysis,
+        PartialAnalysis {
+            cells: vec![
+                (0, 0, ProbeResult::Safe),
+                (0, 1, ProbeResult::Unknown),
+                (0, 3, ProbeResult::Unsafe),
+                (1, 1, ProbeResult::Unknown),
+                (1, 3, ProbeResult::Unsafe),
+            ],
+            unsolved: vec![],
+        }
+    );
+}
+
+#[test]
+fn test_no_budget() {
+    let analysis = analyze_with_budget(&conf(), Duration::from_secs(0));
+    assert!(!analysis.is_complete());
+    assert!(analysis.cells.is_empty());
+    assert_eq!(analysis.unsolved.len(), 5);
+}
+
+#[test]
+fn test_anti_mines() {
+    // Only the rules support anti-mines
+    let conf = Configuration::from("anti-mines: true\n1 ?\n_ _".to_string());
+    let analysis = analyze_with_budget(&conf, Duration::from_secs(60));
+    assert!(analysis.cells.is_empty());
+    assert_eq!(analysis.unsolved, vec![(0, 1), (1, 0), (1, 1)]);
+}
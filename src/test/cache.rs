@@ -0,0 +1,114 @@
+use crate::cache::canonical_key;
+use crate::{CacheStats, Configuration, ProbeResult, SolveOptions, Solver, Strategy};
+
+fn conf(raw_conf: &str) -> Configuration {
+    Configuration::from(raw_conf.trim().to_string())
+}
+
+#[test]
+fn test_canonical_key() {
+    let board = conf(
+        "
+        0 0 0 0 0
+        0 0 0 0 0
+        0 0 1 1 1
+        0 0 1 _ ?
+    ",
+    );
+    let key = canonical_key(&board, true);
+    assert_eq!(canonical_key(&board.rotate90(), true), key);
+    assert_eq!(canonical_key(&board.mirror_h(), true), key);
+    assert_eq!(canonical_key(&board.mirror_v().rotate90(), true), key);
+    assert_eq!(canonical_key(&conf("1 1 1\n1 _ ?"), true), key);
+
+    assert_ne!(canonical_key(&conf("1 2 1\n1 _ ?"), true), key);
+    assert_ne!(canonical_key(&conf("1 1 1\n1 ? _"), true), key);
+    assert_ne!(canonical_key(&board, false), key);
+}
+
+#[test]
+fn test_solver_cache() {
+    let solver = Solver::new().with_cache(10);
+    assert_eq!(
+        solver.check(&conf("1 1 1\n_ _ ?")).result,
+        ProbeResult::Safe
+    );
+    assert_eq!(
+        solver.check(&conf("? _ _\n1 1 1")).result,
+        ProbeResult::Safe
+    );
+    assert_eq!(
+        solver.cache_stats(),
+        Some(CacheStats {
+            hits: 1,
+            misses: 1,
+            len: 1
+        })
+    );
+
+    assert_eq!(Solver::new().cache_stats(), None);
+}
+
+#[test]
+fn test_distant_numbers() {
+    // The 1 in the corner doesn't share covered cells with the numbers around the probe
+    let solver = Solver::new().with_cache(10);
+    for raw_conf in ["1 1 1 _ _ _\n_ _ ? _ _ _", "1 1 1 _ _ 1\n_ _ ? _ _ _"] {
+        assert_eq!(solver.check(&conf(raw_conf)).result, ProbeResult::Safe);
+    }
+    assert_eq!(
+        solver.cache_stats(),
+        Some(CacheStats {
+            hits: 1,
+            misses: 1,
+            len: 1
+        })
+    );
+}
+
+#[test]
+fn test_least_recently_used() {
+    let solver = Solver::new().with_cache(2);
+    for raw_conf in [
+        "1 1 1\n_ _ ?",
+        "1 2 1\n_ _ ?",
+        "1 1 1\n_ _ ?",
+        // Evicts the second board, which was used less recently
+        "1 _\n_ ?",
+        "1 2 1\n_ _ ?",
+    ] {
+        solver.check(&conf(raw_conf));
+    }
+    assert_eq!(
+        solver.cache_stats(),
+        Some(CacheStats {
+            hits: 1,
+            misses: 4,
+            len: 2
+        })
+    );
+}
+
+#[test]
+fn test_hybrid_cutoff() {
+    // The same pattern as beyond_the_rules, padded with numbers whose covered cells
+    // push the frontier of the board over the limit of the search
+    let small = "? _ 3 _\n1 _ 3 _";
+    let padded = "? _ 3 _ _ _ 1 1 1 1 1\n1 _ 3 _ _ _ _ _ _ _ _";
+    let hybrid = SolveOptions::new().with_strategy(Strategy::Hybrid { frontier_limit: 8 });
+    let check = |raw_conf| {
+        Solver::new()
+            .with_options(hybrid.clone())
+            .check(&conf(raw_conf))
+    };
+    assert_eq!(check(small).result, ProbeResult::Safe);
+    assert_eq!(check(padded).result, ProbeResult::Unknown);
+
+    // The cache doesn't change the verdicts, whichever board comes first
+    for order in [[small, padded], [padded, small]] {
+        let solver = Solver::new().with_options(hybrid.clone()).with_cache(10);
+        for raw_conf in order {
+            assert_eq!(solver.check(&conf(raw_conf)).result, check(raw_conf).result);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::corpus::{Entry, BOARDS};
+use crate::{check_configuration, exact_verdict, iterations};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// Describes the verdicts of both engines on the probe of `entry`,
+/// and the board with every cell the rules decide
+fn analysis(entry: &Entry) -> String {
+    let conf = entry.configuration();
+    let search = if conf.anti_mines() {
+        String::from("unsupported")
+    } else {
+        exact_verdict(&conf).map_or(String::from("inconsistent"), |verdict| {
+            format!("{:?}", verdict)
+        })
+    };
+    let deduced = iterations(&conf).pop().unwrap();
+    format!(
+        "rules: {:?}\nsearch: {}\n\n{}\n",
+        check_configuration(conf),
+        search,
+        deduced
+    )
+}
+
+/// Compares `actual` with the snapshot `name`.
+/// Run the tests with `UPDATE_SNAPSHOTS=1` to accept new snapshots.
+fn assert_snapshot(name: &str, actual: &str) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "src", "test", "snapshots"]
+        .iter()
+        .collect::<PathBuf>()
+        .join(format!("{}.snap", name));
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        expected == actual,
+        "Snapshot {} changed, run with UPDATE_SNAPSHOTS=1 to accept it\nexpected:\n{}\nactual:\n{}",
+        name,
+        expected,
+        actual
+    );
+}
+
+#[test]
+fn test_corpus() {
+    for entry in BOARDS {
+        assert_snapshot(entry.name, &analysis(entry));
+    }
+}
+
+#[test]
+fn test_corpus_files() {
+    let dir: PathBuf = [env!("CARGO_MANIFEST_DIR"), "corpus"].iter().collect();
+    let files = fs::read_dir(dir).unwrap().count();
+    assert_eq!(
+        files,
+        BOARDS.len(),
+        "Every file in corpus/ must be listed in BOARDS"
+    );
+}
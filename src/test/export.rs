@@ -0,0 +1,40 @@
+use crate::export::{to_svg, SvgOptions};
+use crate::Configuration;
+
+fn conf() -> Configuration {
+    Configuration::from(
+        "
+        1 1 1
+        1 * 1
+        _ _ ?
+        "
+        .trim()
+        .to_string(),
+    )
+}
+
+#[test]
+fn test_svg() {
+    let svg = to_svg(&conf(), &SvgOptions::default());
+    assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="72" height="72""#));
+    assert_eq!(svg.matches(r##"fill="#0000ff">1</text>"##).count(), 5);
+    assert_eq!(svg.matches(r#"fill="black"/>"#).count(), 1);
+    assert!(!svg.contains("fill-opacity"));
+}
+
+#[test]
+fn test_svg_overlay() {
+    let options = SvgOptions {
+        overlay: true,
+        ..SvgOptions::default()
+    };
+    let svg = to_svg(&conf(), &options);
+    assert_eq!(svg.matches(r#"fill="green" fill-opacity="0.4""#).count(), 3);
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn test_png() {
+    let png = crate::export::to_png(&conf(), &SvgOptions::default());
+    assert!(png.starts_with(b"\x89PNG"));
+}
@@ -0,0 +1,70 @@
+use crate::http::router;
+use crate::Configuration;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+
+/// Sends a raw HTTP request to the API and returns the response
+fn request(path: &str, body: &str) -> String {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let listener = runtime
+        .block_on(tokio::net::TcpListener::bind("127.0.0.1:0"))
+        .unwrap();
+    let address = listener.local_addr().unwrap();
+    runtime.spawn(async move { axum::serve(listener, router()).await });
+
+    let mut stream = TcpStream::connect(address).unwrap();
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        path,
+        body.len(),
+        body
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn test_analyze() {
+    let response = request("/analyze", r#"{"board": "1 1 1\n_ _ ?"}"#);
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.contains(r#""probe":"safe""#));
+}
+
+#[test]
+fn test_invalid_board() {
+    let response = request("/analyze", r#"{"board": "9 ?"}"#);
+    assert!(response.starts_with("HTTP/1.1 400 Bad Request"));
+    assert!(response.ends_with(r#"{"error":"Invalid number of mines: 9"}"#));
+}
+
+#[test]
+fn test_timeout() {
+    let response = request("/analyze", r#"{"board": "0 ?", "timeout_ms": 0}"#);
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.contains(r#""probe":"timed_out""#));
+}
+
+#[test]
+fn test_generate() {
+    let body = r#"{"rows": 9, "cols": 9, "mines": 10, "seed": 1}"#;
+    let response = request("/generate", body);
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    // The headers hold the date, only the body is the same for the same seed
+    let json = |response: &str| response[response.find("\r\n\r\n").unwrap() + 4..].to_string();
+    assert_eq!(json(&request("/generate", body)), json(&response));
+
+    let board: serde_json::Value = serde_json::from_str(&json(&response)).unwrap();
+    let conf = Configuration::from(board["board"].as_str().unwrap().to_string());
+    assert_eq!((conf.rows(), conf.cols()), (9, 9));
+
+    let response = request(
+        "/generate",
+        r#"{"rows": 2, "cols": 2, "mines": 4, "seed": 1}"#,
+    );
+    assert!(response.starts_with("HTTP/1.1 400 Bad Request"));
+    assert!(response.ends_with(r#"{"error":"Invalid number of mines: 4"}"#));
+}
@@ -0,0 +1,48 @@
+use crate::{check_configuration, minimize, Configuration, ProbeResult};
+
+#[test]
+fn test_minimize() {
+    let conf = Configuration::from(
+        "
+        _ 2 2 _ 2 _
+        2 * 2 * * 3
+        1 _ 2 4 * 3
+        1 ? 3 4 * _
+        2 * * _ 4 _
+        * 3 3 3 _ *
+        "
+        .trim()
+        .to_string(),
+    );
+    let is_safe = |conf: &Configuration| check_configuration(conf.clone()) == ProbeResult::Safe;
+
+    let minimized = minimize(&conf, is_safe);
+    assert!(is_safe(&minimized));
+    assert_eq!(minimized.to_string(), "_ ?\n2 *\n* _");
+}
+
+#[test]
+fn test_minimize_layers() {
+    let conf = Configuration::from(
+        "
+        0 _
+        _ _
+
+        _ _
+        _ ?
+
+        _ _
+        _ _
+        "
+        .trim()
+        .to_string(),
+    );
+    let is_safe = |conf: &Configuration| check_configuration(conf.clone()) == ProbeResult::Safe;
+
+    let minimized = minimize(&conf, is_safe);
+    assert!(is_safe(&minimized));
+    assert_eq!(
+        minimized.to_string(),
+        "topology: layers 2\n0 _\n_ _\n\n_ _\n_ ?"
+    );
+}
@@ -1,3 +1,41 @@
+mod batch;
+mod budget;
+mod cache;
+mod corpus;
+mod export;
+#[cfg(feature = "server")]
+mod http;
+mod minimize;
+mod model;
+mod opening;
+mod options;
+mod parse;
+mod properties;
+mod protocol;
+mod random;
+mod search;
+mod simulate;
+mod solver;
+mod sparse;
+mod stats;
+mod trace;
+mod transform;
+mod validate;
+mod window;
+mod world;
+
+use crate::corpus::BOARDS;
+use crate::Configuration;
+
+/// Returns the corpus board called `name`, so that tests share boards instead of copying them
+fn corpus_board(name: &str) -> Configuration {
+    BOARDS
+        .iter()
+        .find(|entry| entry.name == name)
+        .unwrap_or_else(|| panic!("No corpus board: {}", name))
+        .configuration()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{Configuration, check_configuration, ProbeResult};
@@ -62,6 +100,164 @@ mod tests {
         ", ProbeResult::Unsafe)
     }
 
+    #[test]
+    fn test_subset_safe() {
+        do_test("
+            1 1 1
+            _ _ ?
+        ", ProbeResult::Safe)
+    }
+
+    #[test]
+    fn test_subset_unsafe() {
+        do_test("
+            1 2 1
+            _ _ ?
+        ", ProbeResult::Unsafe)
+    }
+
+    #[test]
+    fn test_square4() {
+        do_test("
+            topology: square4
+            ? 1
+            1 0
+        ", ProbeResult::Unsafe)
+    }
+
+    #[test]
+    fn test_hex() {
+        do_test("
+            topology: hex
+            0 _
+            _ ?
+        ", ProbeResult::Unknown)
+    }
+
+    #[test]
+    fn test_triangle() {
+        do_test("
+            topology: triangle
+            0 _ ?
+            _ _ _
+        ", ProbeResult::Safe)
+    }
+
+    #[test]
+    fn test_wrap() {
+        do_test("
+            wrap: true
+            ? _ _ _
+            _ _ _ _
+            _ _ _ _
+            _ _ _ 0
+        ", ProbeResult::Safe)
+    }
+
+    #[test]
+    fn test_layers() {
+        do_test("
+            0 _
+            _ _
+
+            ? _
+            _ _
+        ", ProbeResult::Safe);
+        do_test("
+            _
+            _
+            0
+
+            ?
+            _
+            _
+        ", ProbeResult::Unknown);
+        // Rows wrap within the layers like the columns and the layers
+        do_test("
+            wrap: true
+            _ _ _
+            _ _ _
+            0 _ _
+
+            ? _ _
+            _ _ _
+            _ _ _
+        ", ProbeResult::Safe)
+    }
+
+    #[test]
+    fn test_layers_format() {
+        let raw = "1 _\n_ _\n\n_ _\n_ ?";
+        let conf = Configuration::from(raw.to_string());
+        assert_eq!(conf.topology().name(), "layers 2");
+        assert_eq!(conf.to_string(), format!("topology: layers 2\n{}", raw));
+        assert_eq!(Configuration::from(conf.to_string()), conf);
+        assert_eq!(Configuration::from("_ _ _\n_ _ _\n_ _ _\n\n_ _ _\n_ 26 _\n_ _ _\n\n_ _ _\n_ _ _\n_ _ ?".to_string()).rows(), 9);
+    }
+
+    #[test]
+    fn test_knight() {
+        do_test("
+            topology: knight
+            1 _ _
+            _ _ ?
+            _ * _
+        ", ProbeResult::Safe)
+    }
+
+    #[test]
+    fn test_custom_mask() {
+        do_test("
+            topology: mask 0,2 0,-2
+            1 _ ?
+            _ _ 0
+        ", ProbeResult::Unsafe)
+    }
+
+    #[test]
+    fn test_anti_mines() {
+        do_test("
+            anti-mines: true
+            * 0 ?
+        ", ProbeResult::Unsafe);
+        do_test("
+            anti-mines: true
+            0 ? _
+            _ -2 _
+        ", ProbeResult::Unknown);
+        do_test("
+            anti-mines: true
+            ! 1 ?
+            _ _ _
+        ", ProbeResult::Unknown);
+        do_test("
+            anti-mines: true
+            0 ? ! -1
+        ", ProbeResult::Safe)
+    }
+
+    #[test]
+    fn test_without_numbers() {
+        do_test("?", ProbeResult::Unknown);
+        do_test("
+            _ _ _
+            _ ? _
+        ", ProbeResult::Unknown);
+        do_test("
+            * _
+            _ ?
+        ", ProbeResult::Unknown);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid board: more than one probe")]
+    fn test_two_probes() {
+        do_test("
+            1 ?
+            _ ?
+        ", ProbeResult::Unknown)
+    }
+
     fn do_test(raw_conf: &str, is_safe: ProbeResult) {
         let conf = Configuration::from(raw_conf.trim().to_string());
         let result = check_configuration(conf);
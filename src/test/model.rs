@@ -0,0 +1,67 @@
+use crate::model::{to_minizinc, to_opb};
+use crate::{exact_verdict, Configuration};
+
+fn conf() -> Configuration {
+    Configuration::from("1 * 2\n_ _ ?".to_string())
+}
+
+#[test]
+fn test_opb() {
+    assert_eq!(
+        to_opb(&conf(), Some(3)),
+        "* #variable= 3 #constraint= 3\n\
+         * x1 is the cell (1,0)\n\
+         * x2 is the cell (1,1)\n\
+         * x3 is the cell (1,2), the probe\n\
+         +1 x1 +1 x2 = 0 ;\n\
+         +1 x2 +1 x3 = 1 ;\n\
+         +1 x1 +1 x2 +1 x3 = 2 ;\n"
+    );
+}
+
+#[test]
+fn test_minizinc() {
+    assert_eq!(
+        to_minizinc(&conf(), None),
+        "% x1 is the cell (1,0)\n\
+         % x2 is the cell (1,1)\n\
+         % x3 is the cell (1,2), the probe\n\
+         array[1..3] of var 0..1: x;\n\
+         constraint x[1] + x[2] = 0;\n\
+         constraint x[2] + x[3] = 1;\n\
+         solve satisfy;\n"
+    );
+}
+
+#[test]
+fn test_unsatisfiable() {
+    // The 1 has no covered neighbours left for its mine
+    let conf = Configuration::from("1 0 ?\n0 0 _".to_string());
+    assert_eq!(exact_verdict(&conf), None);
+    assert_eq!(
+        to_opb(&conf, None),
+        "* #variable= 2 #constraint= 3\n\
+         * x1 is the cell (0,2), the probe\n\
+         * x2 is the cell (1,2)\n\
+         0 x1 = 1 ;\n\
+         +1 x1 +1 x2 = 0 ;\n\
+         +1 x1 +1 x2 = 0 ;\n"
+    );
+    assert_eq!(
+        to_minizinc(&conf, None),
+        "% x1 is the cell (0,2), the probe\n\
+         % x2 is the cell (1,2)\n\
+         array[1..2] of var 0..1: x;\n\
+         constraint false;\n\
+         constraint x[1] + x[2] = 0;\n\
+         constraint x[1] + x[2] = 0;\n\
+         solve satisfy;\n"
+    );
+
+    // No covered cells for the mines of the board
+    let conf = Configuration::from("0 0".to_string());
+    assert_eq!(
+        to_opb(&conf, Some(1)),
+        "* #variable= 1 #constraint= 1\n0 x1 = 1 ;\n"
+    );
+}
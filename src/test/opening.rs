@@ -0,0 +1,75 @@
+use crate::{best_opening, compute_3bv, compute_openings, opening_scores, MineLayout, Seeded};
+
+#[test]
+fn test_reveal() {
+    let layout = MineLayout::new(vec![
+        vec![false, false, false, false],
+        vec![false, false, false, true],
+        vec![true, false, false, false],
+    ]);
+
+    let mut opening = layout.reveal(0, 0);
+    opening.sort();
+    assert_eq!(
+        opening,
+        vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]
+    );
+    assert_eq!(layout.reveal(2, 3), vec![(2, 3)]);
+    assert_eq!(layout.reveal(2, 0), vec![]);
+}
+
+#[test]
+fn test_3bv() {
+    let layout = MineLayout::new(vec![
+        vec![false, false, false, false],
+        vec![false, false, false, true],
+        vec![true, false, false, false],
+    ]);
+    assert_eq!(
+        compute_openings(&layout),
+        vec![vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]]
+    );
+    // The opening and the four numbers outside it
+    assert_eq!(compute_3bv(&layout), 5);
+
+    let empty = MineLayout::new(vec![vec![false; 3]; 2]);
+    assert_eq!(compute_openings(&empty).len(), 1);
+    assert_eq!(compute_3bv(&empty), 1);
+
+    let full = MineLayout::new(vec![vec![true; 3]; 2]);
+    assert_eq!(compute_openings(&full), Vec::<Vec<(usize, usize)>>::new());
+    assert_eq!(compute_3bv(&full), 0);
+}
+
+#[test]
+fn test_opening_scores() {
+    let scores = opening_scores(3, 4, 0, 1, &mut Seeded::new(0));
+    assert_eq!(scores, vec![vec![12.0; 4]; 3]);
+
+    // The first click is never a mine, and the only mine-free cell has mines around
+    let scores = opening_scores(3, 4, 11, 1, &mut Seeded::new(0));
+    assert_eq!(scores, vec![vec![1.0; 4]; 3]);
+}
+
+#[test]
+fn test_best_opening() {
+    let first = best_opening(9, 9, 10, 200, &mut Seeded::new(1));
+    let second = best_opening(9, 9, 10, 200, &mut Seeded::new(1));
+    assert_eq!(first, second);
+
+    // With a single mine, the center always opens 1 cell, the top left corner 40/9 on average
+    // and the other corners 47/9, as a mine under the click moves to the top left corner
+    let (row, col) = best_opening(3, 3, 1, 500, &mut Seeded::new(2));
+    assert!(row != 1 && col != 1);
+    assert_ne!((row, col), (0, 0));
+}
+
+#[test]
+fn test_first_click_relocation() {
+    let scores = opening_scores(3, 3, 1, 2000, &mut Seeded::new(3));
+    assert_eq!(scores[1][1], 1.0);
+    for (row, col) in [(0, 2), (2, 0), (2, 2)] {
+        assert!((scores[row][col] - 47.0 / 9.0).abs() < 0.3);
+    }
+    assert!((scores[0][0] - 40.0 / 9.0).abs() < 0.3);
+}
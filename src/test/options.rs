@@ -0,0 +1,82 @@
+use super::corpus_board;
+use crate::{check_configuration_with, CancellationToken, ProbeResult, SolveOptions, Strategy};
+use std::time::Duration;
+
+#[test]
+fn test_timeout() {
+    let options = SolveOptions::new().with_timeout(Duration::from_secs(0));
+    assert_eq!(
+        check_configuration_with(corpus_board("one_one_one"), &options),
+        ProbeResult::TimedOut
+    );
+
+    let options = SolveOptions::new().with_timeout(Duration::from_secs(60));
+    assert_eq!(
+        check_configuration_with(corpus_board("one_one_one"), &options),
+        ProbeResult::Safe
+    );
+}
+
+#[test]
+fn test_cancel() {
+    let cancel = CancellationToken::new();
+    let options = SolveOptions::new().with_cancel(cancel.clone());
+    assert_eq!(
+        check_configuration_with(corpus_board("one_one_one"), &options),
+        ProbeResult::Safe
+    );
+
+    cancel.cancel();
+    assert_eq!(
+        check_configuration_with(corpus_board("one_one_one"), &options),
+        ProbeResult::Cancelled
+    );
+}
+
+#[test]
+fn test_strategies() {
+    let conf = corpus_board("beyond_the_rules");
+    let check = |strategy| {
+        check_configuration_with(conf.clone(), &SolveOptions::new().with_strategy(strategy))
+    };
+
+    assert_eq!(check(Strategy::RulesOnly), ProbeResult::Unknown);
+    assert_eq!(check(Strategy::SearchOnly), ProbeResult::Safe);
+    assert_eq!(
+        check(Strategy::Hybrid { frontier_limit: 8 }),
+        ProbeResult::Safe
+    );
+    assert_eq!(
+        check(Strategy::Hybrid { frontier_limit: 2 }),
+        ProbeResult::Unknown
+    );
+}
+
+#[test]
+fn test_search_limits() {
+    let search = SolveOptions::new().with_strategy(Strategy::SearchOnly);
+
+    let options = search.clone().with_timeout(Duration::from_secs(0));
+    assert_eq!(
+        check_configuration_with(corpus_board("beyond_the_rules"), &options),
+        ProbeResult::TimedOut
+    );
+
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+    let options = search.with_cancel(cancel);
+    assert_eq!(
+        check_configuration_with(corpus_board("beyond_the_rules"), &options),
+        ProbeResult::Cancelled
+    );
+}
+
+#[test]
+fn test_search_anti_mines() {
+    // The search doesn't support anti-mines, so the rules check the board
+    let conf = corpus_board("anti_mines");
+    let check = |strategy| {
+        check_configuration_with(conf.clone(), &SolveOptions::new().with_strategy(strategy))
+    };
+    assert_eq!(check(Strategy::SearchOnly), check(Strategy::RulesOnly));
+}
@@ -0,0 +1,38 @@
+use crate::{Configuration, ParseOptions, Square};
+
+fn conf(raw_conf: &str) -> Configuration {
+    Configuration::from(raw_conf.to_string())
+}
+
+#[test]
+fn test_alternative_glyphs() {
+    assert_eq!(conf("1️⃣ □ .\n• ? X"), conf("1 _ _\n* ? *"));
+    // Full-width characters
+    assert_eq!(conf("１ ＿\n＿ ？"), conf("1 _\n_ ?"));
+    assert_eq!(
+        conf("anti-mines: true\n－1 ！\n_ ?"),
+        conf("anti-mines: true\n-1 !\n_ ?")
+    );
+}
+
+#[test]
+fn test_glyph_map() {
+    let options = ParseOptions::new()
+        .with_glyph("🟦", Square::Empty)
+        .with_glyph("💣", Square::Mine)
+        .with_unspaced(true);
+    let board = Configuration::from_with("1️⃣🟦🟦\n💣 2?".to_string(), &options);
+    assert_eq!(board, conf("1 _ _\n* 2 ?"));
+
+    let rendered = board.render(&options);
+    assert_eq!(rendered, "1🟦🟦\n💣2?");
+    assert_eq!(Configuration::from_with(rendered, &options), board);
+
+    assert_eq!(board.render(&ParseOptions::new()), board.to_string());
+}
+
+#[test]
+#[should_panic(expected = "Invalid square label: ☃")]
+fn test_invalid_glyph() {
+    conf("1 ☃\n_ ?");
+}
@@ -0,0 +1,214 @@
+use crate::{
+    analyze_window, analyze_with_mine_range, check_board, check_configuration, counterexample,
+    deduce, exists_safe_move, solutions, trace, validate_against, what_if, BoundaryPolicy,
+    Configuration, MineLayout, ProbeResult, Rect, Solver, SparseConfiguration, Square,
+};
+use proptest::prelude::*;
+use std::collections::HashMap;
+
+/// A random mine layout together with the revealed cells and a probe
+#[derive(Debug)]
+struct Board {
+    layout: MineLayout,
+    revealed: Vec<Vec<bool>>,
+    probe: (usize, usize),
+}
+
+impl Board {
+    fn configuration(&self) -> Configuration {
+        self.layout
+            .configuration(|row, col| self.revealed[row][col], self.probe)
+    }
+}
+
+fn board() -> impl Strategy<Value = Board> {
+    (1usize..8, 1usize..8)
+        .prop_flat_map(|(rows, cols)| {
+            (
+                prop::collection::vec(prop::collection::vec(prop::bool::weighted(0.2), cols), rows),
+                prop::collection::vec(prop::collection::vec(prop::bool::weighted(0.6), cols), rows),
+                (0..rows, 0..cols),
+            )
+        })
+        .prop_map(|(mines, revealed, probe)| Board {
+            layout: MineLayout::new(mines),
+            revealed,
+            probe,
+        })
+}
+
+proptest! {
+    #[test]
+    fn verdicts_agree_with_layout(board in board()) {
+        let (row, col) = board.probe;
+        match check_configuration(board.configuration()) {
+            ProbeResult::Safe => prop_assert!(!board.layout.is_mine(row, col)),
+            ProbeResult::Unsafe => prop_assert!(board.layout.is_mine(row, col)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn counterexamples_are_consistent(board in board()) {
+        let conf = board.configuration();
+        let (row, col) = board.probe;
+        let witness = counterexample(&conf);
+        match check_configuration(conf.clone()) {
+            ProbeResult::Safe => prop_assert!(witness.is_none()),
+            ProbeResult::Unsafe => prop_assert!(witness.is_some()),
+            _ => {}
+        }
+        if board.layout.is_mine(row, col) {
+            prop_assert!(witness.is_some());
+        }
+        if let Some(witness) = witness {
+            prop_assert!(witness.is_mine(row, col));
+            let revealed = |r: usize, c: usize| !matches!(conf.board[r][c], Square::Empty | Square::Probe);
+            prop_assert_eq!(witness.configuration(revealed, board.probe), conf);
+        }
+    }
+
+    #[test]
+    fn safe_moves_are_safe(board in board()) {
+        let conf = board.configuration();
+        let safe = exists_safe_move(&conf);
+        if let Some((row, col)) = safe {
+            prop_assert!(matches!(conf.board[row][col], Square::Empty | Square::Probe));
+            prop_assert!(!board.layout.is_mine(row, col));
+        }
+        if deduce(&conf).values().any(|square| *square == Square::Safe) {
+            prop_assert!(safe.is_some());
+        }
+    }
+
+    #[test]
+    fn stable_window_verdicts_hold(board in board()) {
+        let conf = board.configuration();
+        let (row, col) = board.probe;
+        let window = Rect::new(row.saturating_sub(1), col.saturating_sub(1), 3, 3);
+        for verdict in analyze_window(&conf, window, BoundaryPolicy::Pessimistic) {
+            if verdict.stable {
+                let mine = board.layout.is_mine(verdict.row, verdict.col);
+                prop_assert_eq!(verdict.verdict == ProbeResult::Unsafe, mine);
+            }
+        }
+    }
+
+    #[test]
+    fn what_if_includes_layout(board in board()) {
+        let conf = board.configuration();
+        let (row, col) = board.probe;
+        let outcome = what_if(&conf, row, col);
+        if board.layout.is_mine(row, col) {
+            prop_assert!(outcome.may_explode);
+        } else {
+            prop_assert!(outcome.numbers.contains(&board.layout.mines_around(row, col)));
+        }
+    }
+
+    #[test]
+    fn mine_range_verdicts_hold(board in board()) {
+        let conf = board.configuration();
+        let covered = conf.board.iter().flatten().filter(|s| matches!(s, Square::Empty | Square::Probe)).count();
+        prop_assume!(covered <= 12);
+
+        let mines = (0..conf.rows())
+            .flat_map(|row| (0..conf.cols()).map(move |col| (row, col)))
+            .filter(|&(row, col)| board.layout.is_mine(row, col))
+            .count();
+        let analysis = analyze_with_mine_range(&conf, mines, mines);
+        prop_assert!(analysis.is_some());
+        for (row, col, verdict) in analysis.unwrap().cells {
+            match verdict {
+                ProbeResult::Safe => prop_assert!(!board.layout.is_mine(row, col)),
+                ProbeResult::Unsafe => prop_assert!(board.layout.is_mine(row, col)),
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn solutions_include_layout(board in board()) {
+        let conf = board.configuration();
+        let covered = conf.board.iter().flatten().filter(|s| matches!(s, Square::Empty | Square::Probe)).count();
+        prop_assume!(covered <= 10);
+
+        let (row, col) = board.probe;
+        let verdict = check_configuration(conf.clone());
+        let layouts: Vec<MineLayout> = solutions(&conf).collect();
+        prop_assert!(layouts.contains(&board.layout));
+        for layout in layouts {
+            match verdict {
+                ProbeResult::Safe => prop_assert!(!layout.is_mine(row, col)),
+                ProbeResult::Unsafe => prop_assert!(layout.is_mine(row, col)),
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn verdicts_survive_symmetries(board in board()) {
+        let conf = board.configuration();
+        let result = check_configuration(conf.clone());
+        prop_assert_eq!(check_configuration(conf.rotate90()), result);
+        prop_assert_eq!(check_configuration(conf.mirror_h()), result);
+        prop_assert_eq!(check_configuration(conf.mirror_v()), result);
+    }
+
+    #[test]
+    fn symmetries_are_involutions(board in board()) {
+        let conf = board.configuration();
+        prop_assert_eq!(conf.rotate90().rotate90().rotate90().rotate90(), conf.clone());
+        prop_assert_eq!(conf.mirror_h().mirror_h(), conf.clone());
+        prop_assert_eq!(conf.mirror_v().mirror_v(), conf);
+    }
+
+    #[test]
+    fn cached_verdicts_survive_symmetries(board in board()) {
+        let conf = board.configuration();
+        let solver = Solver::new().with_cache(4);
+        solver.check(&conf);
+        let rotated = conf.rotate90().mirror_h();
+        prop_assert_eq!(solver.check(&rotated).result, check_configuration(rotated));
+        prop_assert_eq!(solver.cache_stats().map(|stats| stats.hits), Some(1));
+    }
+
+    #[test]
+    fn frontier_keeps_verdicts(board in board()) {
+        let conf = board.configuration();
+        prop_assert_eq!(
+            check_configuration(conf.restrict_to_frontier()),
+            check_configuration(conf)
+        );
+    }
+
+    #[test]
+    fn sparse_boards_agree(board in board()) {
+        let conf = board.configuration();
+        let sparse = SparseConfiguration::from(&conf);
+        prop_assert_eq!(check_board(&sparse), check_configuration(conf));
+    }
+
+    #[test]
+    fn traces_explain_every_deduction(board in board()) {
+        let conf = board.configuration();
+        let mut traced = HashMap::new();
+        for step in trace(&conf) {
+            for cell in step.cells {
+                prop_assert!(traced.insert(cell, step.square).is_none());
+            }
+        }
+        prop_assert_eq!(traced, deduce(&conf));
+    }
+
+    #[test]
+    fn parser_round_trips(board in board()) {
+        let conf = board.configuration();
+        prop_assert_eq!(Configuration::from(conf.to_string()), conf);
+    }
+
+    #[test]
+    fn revealed_boards_are_valid(board in board()) {
+        prop_assert_eq!(validate_against(&board.configuration(), &board.layout), vec![]);
+    }
+}
@@ -0,0 +1,59 @@
+use crate::protocol::{handle, serve};
+
+#[test]
+fn test_analyze() {
+    let response = handle(r#"{"cmd": "analyze", "board": "1 1 1\n_ _ ?"}"#);
+    let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+
+    assert_eq!(response["ok"], true);
+    assert_eq!(response["result"]["probe"], "safe");
+    assert_eq!(
+        response["result"]["cells"],
+        serde_json::json!([
+            {"row": 1, "col": 0, "verdict": "safe"},
+            {"row": 1, "col": 1, "verdict": "unsafe"},
+            {"row": 1, "col": 2, "verdict": "safe"},
+        ])
+    );
+    assert_eq!(response["result"]["stats"]["constraints"], 3);
+    assert_eq!(response["result"]["stats"]["variables"], 3);
+}
+
+#[test]
+fn test_errors() {
+    assert_eq!(
+        handle(r#"{"cmd": "analyze", "board": "x ?"}"#),
+        r#"{"ok":false,"error":"Invalid square label: x"}"#
+    );
+    assert_eq!(
+        handle(r#"{"cmd": "analyze", "board": "1 ?\n? _"}"#),
+        r#"{"ok":false,"error":"Invalid board: more than one probe"}"#
+    );
+    assert!(handle(r#"{"cmd": "solve"}"#).starts_with(r#"{"ok":false,"error":"Invalid request"#));
+}
+
+#[test]
+fn test_timeout() {
+    for board in ["0 ?", "0 _"] {
+        let request = serde_json::json!({"cmd": "analyze", "board": board, "timeout_ms": 0});
+        let response = handle(&request.to_string());
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+
+        assert_eq!(response["ok"], true);
+        assert_eq!(response["result"]["probe"], "timed_out");
+        assert_eq!(response["result"]["cells"], serde_json::json!([]));
+        assert_eq!(response["result"]["stats"]["iterations"], 0);
+    }
+}
+
+#[test]
+fn test_serve() {
+    let input = "{\"cmd\": \"analyze\", \"board\": \"0 ?\"}\n\n{\"cmd\": \"analyze\", \"board\": \"_ ?\"}\n";
+    let mut output = vec![];
+    serve(input.as_bytes(), &mut output).unwrap();
+
+    let lines: Vec<&str> = std::str::from_utf8(&output).unwrap().lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].contains(r#""probe":"safe""#));
+    assert!(lines[1].contains(r#""probe":"unknown""#));
+}
@@ -0,0 +1,27 @@
+use crate::{MineLayout, Seeded};
+
+#[test]
+fn test_reproducible_layouts() {
+    let first = MineLayout::random(16, 30, 99, &mut Seeded::new(42));
+    let second = MineLayout::random(16, 30, 99, &mut Seeded::new(42));
+    let other = MineLayout::random(16, 30, 99, &mut Seeded::new(43));
+
+    assert_eq!(first, second);
+    assert_ne!(first, other);
+
+    let mines = (0..16)
+        .flat_map(|row| (0..30).map(move |col| (row, col)))
+        .filter(|(row, col)| first.is_mine(*row, *col))
+        .count();
+    assert_eq!(mines, 99);
+}
+
+#[test]
+fn test_seeded() {
+    let mut rng = Seeded::new(7);
+    assert_eq!(rng.seed(), 7);
+    assert!((0..1000).map(|_| rng.below(10)).all(|n| n < 10));
+    assert_eq!(rng.seed(), 7);
+    assert!(!(0..100).any(|_| rng.chance(0.0)));
+    assert!((0..100).all(|_| rng.chance(1.0)));
+}
@@ -0,0 +1,206 @@
+use crate::{
+    analyze_with_mine_range, analyze_with_mine_range_with, counterexample, counterexample_with,
+    exact_verdict, exact_verdict_with, exists_safe_move, exists_safe_move_with, solutions,
+    solutions_with, what_if, what_if_with, CancellationToken, Configuration, HypotheticalOutcome,
+    Interruption, Label, MineLayout, ProbeResult, SolveOptions,
+};
+use std::time::Duration;
+
+#[test]
+fn test_counterexample() {
+    let conf = Configuration::from("1 _\n_ ?".to_string());
+    let witness = counterexample(&conf).unwrap();
+    assert_eq!(
+        witness,
+        MineLayout::new(vec![vec![false, false], vec![false, true]])
+    );
+
+    let conf = Configuration::from("1 1 1\n_ _ ?".to_string());
+    assert_eq!(counterexample(&conf), None);
+}
+
+#[test]
+fn test_inconsistent() {
+    let conf = Configuration::from("* 0\n_ ?".to_string());
+    assert_eq!(counterexample(&conf), None);
+}
+
+#[test]
+fn test_solutions() {
+    let conf = Configuration::from("1 _\n_ ?".to_string());
+    let layouts: Vec<MineLayout> = solutions(&conf).collect();
+    assert_eq!(
+        layouts,
+        vec![
+            MineLayout::new(vec![vec![false, false], vec![false, true]]),
+            MineLayout::new(vec![vec![false, false], vec![true, false]]),
+            MineLayout::new(vec![vec![false, true], vec![false, false]]),
+        ]
+    );
+
+    let conf = Configuration::from("* 0\n_ ?".to_string());
+    assert_eq!(solutions(&conf).count(), 0);
+
+    let conf = Configuration::from("_ _ ?".to_string());
+    assert_eq!(solutions(&conf).count(), 8);
+}
+
+#[test]
+fn test_exact_verdict() {
+    let verdict = |raw: &str| exact_verdict(&Configuration::from(raw.to_string()));
+    assert_eq!(verdict("1 1 1\n_ _ ?"), Some(ProbeResult::Safe));
+    assert_eq!(verdict("1 2 1\n_ _ ?"), Some(ProbeResult::Unsafe));
+    assert_eq!(verdict("1 _\n_ ?"), Some(ProbeResult::Unknown));
+    assert_eq!(verdict("* 0\n_ ?"), None);
+    assert_eq!(verdict("* _\n_ ?"), Some(ProbeResult::Unknown));
+}
+
+#[test]
+fn test_exists_safe_move() {
+    let safe_move = |raw: &str| exists_safe_move(&Configuration::from(raw.to_string()));
+    assert_eq!(safe_move("1 1 1\n_ _ ?"), Some((1, 0)));
+    assert_eq!(safe_move("1 _\n_ ?"), None);
+    assert_eq!(safe_move("2 _\n_ ?"), None);
+    // No numbers, every covered cell may be a mine
+    assert_eq!(safe_move("_ _ ?"), None);
+}
+
+#[test]
+fn test_what_if() {
+    let outcome = |raw: &str, row, col| what_if(&Configuration::from(raw.to_string()), row, col);
+    let expected = |may_explode, numbers: Vec<Label>| HypotheticalOutcome {
+        may_explode,
+        numbers,
+    };
+
+    assert_eq!(outcome("1 1 1\n_ _ ?", 1, 2), expected(false, vec![1]));
+    assert_eq!(outcome("1 1 1\n_ _ ?", 1, 1), expected(true, vec![]));
+    assert_eq!(outcome("1 _\n_ ?", 1, 1), expected(true, vec![1]));
+    // No numbers constrain the neighbours
+    assert_eq!(
+        outcome("_ _ _\n_ ? _", 1, 1),
+        expected(true, vec![0, 1, 2, 3, 4, 5])
+    );
+    assert_eq!(outcome("0 1\n_ ?", 1, 1), expected(false, vec![]));
+}
+
+#[test]
+fn test_what_if_limits() {
+    let conf = Configuration::from("1 1 1\n_ _ ?".to_string());
+    let outcome = |options| what_if_with(&conf, 1, 2, &options);
+    assert_eq!(
+        outcome(SolveOptions::new().with_timeout(Duration::from_secs(60))),
+        Ok(what_if(&conf, 1, 2))
+    );
+    assert_eq!(
+        outcome(SolveOptions::new().with_timeout(Duration::ZERO)),
+        Err(Interruption::TimedOut)
+    );
+
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+    assert_eq!(
+        outcome(SolveOptions::new().with_cancel(cancel)),
+        Err(Interruption::Cancelled)
+    );
+}
+
+#[test]
+fn test_search_limits() {
+    let conf = Configuration::from("1 _\n_ ?".to_string());
+    let unlimited = SolveOptions::new().with_timeout(Duration::from_secs(60));
+    assert_eq!(
+        exact_verdict_with(&conf, &unlimited),
+        Ok(exact_verdict(&conf))
+    );
+    assert_eq!(
+        counterexample_with(&conf, &unlimited),
+        Ok(counterexample(&conf))
+    );
+    assert_eq!(
+        exists_safe_move_with(&conf, &unlimited),
+        Ok(exists_safe_move(&conf))
+    );
+    assert_eq!(
+        solutions_with(&conf, &unlimited).collect::<Result<Vec<_>, _>>(),
+        Ok(solutions(&conf).collect())
+    );
+    assert_eq!(
+        analyze_with_mine_range_with(&conf, 1, 2, &unlimited),
+        Ok(analyze_with_mine_range(&conf, 1, 2))
+    );
+
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+    let cancelled = SolveOptions::new().with_cancel(cancel);
+    let interrupted = Some(Interruption::Cancelled);
+    assert_eq!(exact_verdict_with(&conf, &cancelled).err(), interrupted);
+    assert_eq!(counterexample_with(&conf, &cancelled).err(), interrupted);
+    assert_eq!(exists_safe_move_with(&conf, &cancelled).err(), interrupted);
+    assert_eq!(
+        solutions_with(&conf, &cancelled)
+            .map(Result::err)
+            .collect::<Vec<_>>(),
+        vec![interrupted]
+    );
+    assert_eq!(
+        analyze_with_mine_range_with(&conf, 1, 2, &cancelled).err(),
+        interrupted
+    );
+}
+
+#[test]
+fn test_independent_components() {
+    // Each pair of numbers shares one or two mines with its covered cells, independently
+    // of the other pairs, and the 0 at the end is next to a mine
+    let mut covered = String::from("?");
+    let mut numbers = String::from("1 2 * *");
+    for _ in 1..20 {
+        covered += " _ _ _ _";
+        numbers += " 2 2 * *";
+    }
+    covered += " _ _ _ _";
+    numbers += " 0";
+    let conf = Configuration::from(format!("{}\n{}", covered, numbers));
+    assert_eq!(exact_verdict(&conf), None);
+}
+
+#[test]
+fn test_mine_range() {
+    let conf = Configuration::from("1 _ _\n_ _ _".to_string());
+    let unknown = ProbeResult::Unknown;
+
+    // The 1 takes the only mine, the cells away from it are safe
+    let analysis = analyze_with_mine_range(&conf, 1, 1).unwrap();
+    assert_eq!(
+        analysis.cells,
+        vec![
+            (0, 1, unknown),
+            (0, 2, ProbeResult::Safe),
+            (1, 0, unknown),
+            (1, 1, unknown),
+            (1, 2, ProbeResult::Safe),
+        ]
+    );
+    assert_eq!(analysis.decided_by_range, vec![(0, 2), (1, 2)]);
+
+    let analysis = analyze_with_mine_range(&conf, 3, 10).unwrap();
+    assert_eq!(analysis.cells[1], (0, 2, ProbeResult::Unsafe));
+    assert_eq!(analysis.decided_by_range, vec![(0, 2), (1, 2)]);
+
+    let analysis = analyze_with_mine_range(&conf, 1, 2).unwrap();
+    assert!(analysis.cells.iter().all(|cell| cell.2 == unknown));
+    assert_eq!(analysis.decided_by_range, vec![]);
+
+    assert_eq!(analyze_with_mine_range(&conf, 0, 0), None);
+    assert_eq!(analyze_with_mine_range(&conf, 4, 5), None);
+
+    // The mine already shown counts as well
+    let conf = Configuration::from("* 1\n_ _".to_string());
+    assert_eq!(analyze_with_mine_range(&conf, 0, 0), None);
+    let analysis = analyze_with_mine_range(&conf, 1, 1).unwrap();
+    assert_eq!(
+        analysis.cells,
+        vec![(1, 0, ProbeResult::Safe), (1, 1, ProbeResult::Safe)]
+    );
+}
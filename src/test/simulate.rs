@@ -0,0 +1,59 @@
+use crate::simulate::{simulate, BoardParams, BoardView, Move, SolverBot, Strategy};
+
+#[test]
+fn test_trivial_games() {
+    let empty = BoardParams {
+        rows: 5,
+        cols: 5,
+        mines: 0,
+    };
+    let report = simulate(&mut SolverBot::new(1), empty, 3, 1);
+    assert_eq!((report.games, report.wins, report.guesses), (3, 3, 0));
+
+    // The first click is safe and all other cells are mines
+    let full = BoardParams {
+        rows: 3,
+        cols: 3,
+        mines: 8,
+    };
+    let report = simulate(&mut SolverBot::new(1), full, 3, 1);
+    assert_eq!((report.wins, report.guesses), (3, 0));
+}
+
+#[test]
+fn test_no_games() {
+    let report = simulate(&mut SolverBot::new(1), BoardParams::beginner(), 0, 1);
+    assert_eq!(report.win_rate(), 0.0);
+    assert_eq!(report.average_guesses(), 0.0);
+}
+
+#[test]
+fn test_reproducible() {
+    let first = simulate(&mut SolverBot::new(7), BoardParams::beginner(), 20, 7);
+    let second = simulate(&mut SolverBot::new(7), BoardParams::beginner(), 20, 7);
+
+    assert_eq!(first.wins, second.wins);
+    assert_eq!(first.guesses, second.guesses);
+    assert!(first.wins > 0 && first.wins <= 20);
+    assert!(first.win_rate() <= 1.0);
+}
+
+/// Always guesses the first covered cell
+struct FirstCovered;
+
+impl Strategy for FirstCovered {
+    fn choose_move(&mut self, view: &BoardView) -> Move {
+        let (row, col) = view.covered()[0];
+        Move::Guess(row, col)
+    }
+}
+
+#[test]
+fn test_custom_strategy() {
+    let bot = simulate(&mut SolverBot::new(3), BoardParams::beginner(), 30, 3);
+    let naive = simulate(&mut FirstCovered, BoardParams::beginner(), 30, 3);
+
+    assert!(naive.guesses >= naive.games - naive.wins);
+    assert!(bot.win_rate() >= naive.win_rate());
+    assert!(bot.average_guesses() <= naive.average_guesses());
+}
@@ -0,0 +1,71 @@
+use super::corpus_board;
+use crate::{EngineKind, ProbeResult, SolveOptions, Solver, Strategy};
+use std::thread;
+
+#[test]
+fn test_engines() {
+    let conf = corpus_board("beyond_the_rules");
+    let check = |strategy| {
+        let verdict = Solver::new()
+            .with_options(SolveOptions::new().with_strategy(strategy))
+            .check(&conf);
+        (verdict.result, verdict.engine)
+    };
+
+    assert_eq!(
+        check(Strategy::RulesOnly),
+        (ProbeResult::Unknown, EngineKind::Rules)
+    );
+    assert_eq!(
+        check(Strategy::SearchOnly),
+        (ProbeResult::Safe, EngineKind::Search)
+    );
+    assert_eq!(
+        check(Strategy::Hybrid { frontier_limit: 8 }),
+        (ProbeResult::Safe, EngineKind::Search)
+    );
+    assert_eq!(
+        check(Strategy::Hybrid { frontier_limit: 2 }),
+        (ProbeResult::Unknown, EngineKind::Rules)
+    );
+}
+
+#[test]
+fn test_stats() {
+    let conf = corpus_board("one_one_one");
+    let hybrid = SolveOptions::new().with_strategy(Strategy::Hybrid { frontier_limit: 8 });
+    let verdict = Solver::new().with_options(hybrid).check(&conf);
+    assert_eq!(verdict.result, ProbeResult::Safe);
+    assert_eq!(verdict.engine, EngineKind::Rules);
+    assert_eq!(verdict.stats.constraints, 3);
+
+    let search = SolveOptions::new().with_strategy(Strategy::SearchOnly);
+    let verdict = Solver::new().with_options(search).check(&conf);
+    assert_eq!(verdict.result, ProbeResult::Safe);
+    assert_eq!(verdict.stats.constraints, 0);
+}
+
+#[test]
+fn test_shared_between_threads() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Solver>();
+
+    let solver = Solver::new().with_cache(10);
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let solver = solver.clone();
+            thread::spawn(move || {
+                let conf = corpus_board("one_one_one");
+                solver.check(&conf).result
+            })
+        })
+        .collect();
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), ProbeResult::Safe);
+    }
+
+    // The clones share one cache
+    let stats = solver.cache_stats().unwrap();
+    assert_eq!(stats.hits + stats.misses, 4);
+    assert_eq!(stats.len, 1);
+}
@@ -0,0 +1,24 @@
+use crate::{check_board, Configuration, ProbeResult, Rect, SparseConfiguration, Square};
+
+#[test]
+fn test_huge_board() {
+    let mut world = SparseConfiguration::new(10_000, 10_000);
+    world.set(5_000, 5_000, Square::Number(1));
+    world.set(5_000, 5_001, Square::Number(1));
+    world.set(5_001, 5_000, Square::Number(1));
+    world.set(5_001, 5_001, Square::Mine);
+    world.set(4_999, 5_001, Square::Probe);
+
+    assert_eq!(check_board(&world), ProbeResult::Safe);
+
+    let expected = Configuration::from(
+        "
+        _ _ ?
+        _ 1 s
+        _ s *
+        "
+        .trim()
+        .to_string(),
+    );
+    assert_eq!(world.window(Rect::new(4_999, 4_999, 3, 3)), expected);
+}
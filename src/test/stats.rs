@@ -0,0 +1,39 @@
+use crate::{check_board_stats, Configuration, ProbeResult, SolveOptions};
+use std::time::Duration;
+
+#[test]
+fn test_stats() {
+    let conf = Configuration::from("1 1 1\n_ _ ?".to_string());
+    let (result, stats) = check_board_stats(&conf, &SolveOptions::new());
+
+    assert_eq!(result, ProbeResult::Safe);
+    assert_eq!(stats.variables, 3);
+    assert_eq!(stats.constraints, 3);
+    assert_eq!(stats.unique_constraints, 3);
+    assert_eq!(stats.deduced, 3);
+    assert!(stats.iterations >= 2);
+    assert!(stats.subset_pairs >= 2);
+    assert_eq!(stats.total_time(), stats.setup_time + stats.rules_time);
+}
+
+#[test]
+fn test_interrupted_stats() {
+    let conf = Configuration::from("1 1 1\n_ _ ?".to_string());
+    let options = SolveOptions::new().with_timeout(Duration::from_secs(0));
+    let (result, stats) = check_board_stats(&conf, &options);
+
+    assert_eq!(result, ProbeResult::TimedOut);
+    assert_eq!(stats.iterations, 0);
+    assert_eq!(stats.deduced, 0);
+}
+
+#[test]
+fn test_duplicate_constraints() {
+    let conf = Configuration::from("1 1\n_ ?".to_string());
+    let (result, stats) = check_board_stats(&conf, &SolveOptions::new());
+
+    assert_eq!(result, ProbeResult::Unknown);
+    assert_eq!(stats.constraints, 2);
+    assert_eq!(stats.unique_constraints, 1);
+    assert_eq!(stats.subset_pairs, 0);
+}
@@ -0,0 +1,72 @@
+use crate::{iterations, render_trace, trace, Configuration, DeductionStep, Reason, Square};
+
+#[test]
+fn test_trace() {
+    let conf = Configuration::from("1 1 1\n_ _ ?".to_string());
+    let steps = trace(&conf);
+
+    assert_eq!(
+        steps[0],
+        DeductionStep {
+            iteration: 1,
+            reason: Reason::Subset {
+                smaller: ((0, 0), 1),
+                larger: ((0, 1), 1),
+                mines: 0,
+            },
+            cells: vec![(1, 2)],
+            square: Square::Safe,
+        }
+    );
+    assert_eq!(
+        render_trace(&steps),
+        "the covered neighbours of (0,0)=1 are among those of (0,1)=1, so the rest hold 0 mines ⇒ (1,2) safe\n\
+         the covered neighbours of (0,2)=1 are among those of (0,1)=1, so the rest hold 0 mines ⇒ (1,0) safe\n\
+         cell (0,0)=1 needs 1 more mines among its covered neighbours ⇒ (1,1) mines\n"
+    );
+}
+
+#[test]
+fn test_trace_numbers() {
+    let conf = Configuration::from("* 1 _\n_ _ ?".to_string());
+    let steps = trace(&conf);
+
+    assert_eq!(steps.len(), 1);
+    assert_eq!(
+        steps[0].reason,
+        Reason::Number {
+            cell: (0, 1),
+            label: 1,
+            mines: 0
+        }
+    );
+    assert_eq!(steps[0].cells, vec![(0, 2), (1, 0), (1, 1), (1, 2)]);
+}
+
+#[test]
+fn test_iterations() {
+    let conf = Configuration::from("1 1 1\n_ _ ?".to_string());
+    let boards: Vec<String> = iterations(&conf)
+        .iter()
+        .map(|board| board.to_string())
+        .collect();
+    assert_eq!(boards, vec!["1 1 1\n_ _ ?", "1 1 1\ns _ s", "1 1 1\ns * s"]);
+}
+
+#[test]
+fn test_trace_differences() {
+    // The difference of the 1 and the 2 is a subset of the cells around the other 1
+    let conf = super::corpus_board("chained_subsets");
+    assert_eq!(
+        trace(&conf),
+        vec![DeductionStep {
+            iteration: 2,
+            reason: Reason::Difference {
+                cells: vec![(0, 3), (2, 2), (2, 3)],
+                mines: 0,
+            },
+            cells: vec![(0, 3), (2, 2), (2, 3)],
+            square: Square::Safe,
+        }]
+    );
+}
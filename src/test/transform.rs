@@ -0,0 +1,140 @@
+use crate::{Configuration, Rect};
+
+fn conf(raw_conf: &str) -> Configuration {
+    Configuration::from(raw_conf.trim().to_string())
+}
+
+#[test]
+fn test_crop() {
+    let board = conf(
+        "
+        _ 1 _ _
+        1 1 1 _
+        _ _ _ ?
+    ",
+    );
+    let expected = conf(
+        "
+        _ 1 _
+        1 1 s
+        _ _ _
+    ",
+    );
+    assert_eq!(board.crop(Rect::new(0, 0, 3, 3)), expected);
+}
+
+#[test]
+fn test_crop_layers() {
+    // Crops keep whole layers
+    let board = conf(
+        "
+        1 _
+        _ _
+
+        _ _
+        _ ?
+
+        _ _
+        _ _
+    ",
+    );
+    let expected = conf(
+        "
+        topology: layers 2
+        _ _
+        _ ?
+    ",
+    );
+    assert_eq!(board.crop(Rect::new(2, 0, 1, 2)), expected);
+
+    let expected = conf(
+        "
+        1 _
+        _ _
+
+        _ _
+        _ ?
+    ",
+    );
+    assert_eq!(board.crop(Rect::new(1, 0, 2, 2)), expected);
+    assert_eq!(board.restrict_to_frontier(), expected);
+}
+
+#[test]
+fn test_rotate90() {
+    let board = conf(
+        "
+        topology: knight
+        1 _ ?
+        * _ _
+    ",
+    );
+    let expected = conf(
+        "
+        topology: knight
+        * 1
+        _ _
+        _ ?
+    ",
+    );
+    assert_eq!(board.rotate90(), expected);
+}
+
+#[test]
+fn test_mirror_mask() {
+    let board = conf(
+        "
+        topology: mask 0,1
+        1 ?
+    ",
+    );
+    let expected = conf(
+        "
+        topology: mask 0,-1
+        ? 1
+    ",
+    );
+    assert_eq!(board.mirror_h(), expected);
+}
+
+#[test]
+fn test_restrict_to_frontier() {
+    let board = conf(
+        "
+        0 0 0 0 0
+        0 0 0 0 0
+        0 0 1 1 1
+        0 0 1 _ ?
+    ",
+    );
+    let expected = conf(
+        "
+        s s s s
+        s 1 1 1
+        s 1 _ ?
+    ",
+    );
+    assert_eq!(board.restrict_to_frontier(), expected);
+}
+
+#[test]
+fn test_restrict_to_frontier_edge() {
+    // The 4 would lose most of its neighbours to the cropping
+    let board = conf(
+        "
+        0 0 0 ?
+        2 2 1 _
+        * * 2 _
+        * 4 * _
+    ",
+    );
+    let expected = conf(
+        "
+        s 0 ?
+        s 1 _
+        * 2 _
+        s * _
+    ",
+    );
+    assert_eq!(board.restrict_to_frontier(), expected);
+}
@@ -0,0 +1,53 @@
+use crate::{validate_against, Configuration, MineLayout, Violation};
+
+fn layout() -> MineLayout {
+    MineLayout::new(vec![vec![false, true, false], vec![false, false, false]])
+}
+
+#[test]
+fn test_valid_board() {
+    let conf = Configuration::from("1 * 1\n1 1 ?".to_string());
+    assert_eq!(validate_against(&conf, &layout()), vec![]);
+
+    let covered = Configuration::from("_ _ _\n_ _ ?".to_string());
+    assert_eq!(validate_against(&covered, &layout()), vec![]);
+}
+
+#[test]
+fn test_violations() {
+    let conf = Configuration::from("* s 1\n2 1 ?".to_string());
+    assert_eq!(
+        validate_against(&conf, &layout()),
+        vec![
+            Violation::FalseMine { row: 0, col: 0 },
+            Violation::RevealedMine { row: 0, col: 1 },
+            Violation::WrongNumber {
+                row: 1,
+                col: 0,
+                shown: 2,
+                actual: 1
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_anti_mines() {
+    let layout = MineLayout::new(vec![vec![true, false, false]]);
+    let conf = Configuration::from("anti-mines: true\n* 0 !\n".to_string());
+    assert_eq!(validate_against(&conf, &layout), vec![]);
+}
+
+#[test]
+fn test_size_mismatch() {
+    let conf = Configuration::from("_ ?".to_string());
+    assert_eq!(
+        validate_against(&conf, &layout()),
+        vec![Violation::SizeMismatch {
+            rows: 1,
+            cols: 2,
+            layout_rows: 2,
+            layout_cols: 3
+        }]
+    );
+}
@@ -0,0 +1,52 @@
+use crate::{analyze_window, BoundaryPolicy, Configuration, ProbeResult, Rect, WindowVerdict};
+
+fn verdict(row: usize, col: usize, verdict: ProbeResult, stable: bool) -> WindowVerdict {
+    WindowVerdict {
+        row,
+        col,
+        verdict,
+        stable,
+    }
+}
+
+#[test]
+fn test_boundary_policies() {
+    // The 1 sees a covered cell on each side, one of them outside the window
+    let conf = Configuration::from("_ 1 _ _".to_string());
+    let window = Rect::new(0, 1, 1, 2);
+
+    assert_eq!(
+        analyze_window(&conf, window, BoundaryPolicy::Unconstrained),
+        vec![]
+    );
+    assert_eq!(
+        analyze_window(&conf, window, BoundaryPolicy::Pessimistic),
+        vec![verdict(0, 2, ProbeResult::Safe, false)]
+    );
+    assert_eq!(
+        analyze_window(&conf, window, BoundaryPolicy::Optimistic),
+        vec![verdict(0, 2, ProbeResult::Unsafe, false)]
+    );
+}
+
+#[test]
+fn test_stable_verdicts() {
+    let conf = Configuration::from("_ 1 _ 0 _\n_ _ _ _ _".to_string());
+    let window = Rect::new(0, 2, 2, 3);
+
+    // The 1 is outside the window, so the mine it implies isn't found
+    let expected = vec![
+        verdict(0, 2, ProbeResult::Safe, true),
+        verdict(0, 4, ProbeResult::Safe, true),
+        verdict(1, 2, ProbeResult::Safe, true),
+        verdict(1, 3, ProbeResult::Safe, true),
+        verdict(1, 4, ProbeResult::Safe, true),
+    ];
+    for boundary in [
+        BoundaryPolicy::Unconstrained,
+        BoundaryPolicy::Pessimistic,
+        BoundaryPolicy::Optimistic,
+    ] {
+        assert_eq!(analyze_window(&conf, window, boundary), expected);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{check_configuration, InfiniteWorld, ProbeResult, Reveal};
+
+/// Returns a world with a zero cell at the origin
+fn open_world(density: f64) -> InfiniteWorld {
+    (0..)
+        .map(|seed| InfiniteWorld::new(seed, density))
+        .find_map(|mut world| {
+            if !world.is_mine(0, 0) && world.mines_around(0, 0) == 0 {
+                Some(world)
+            } else {
+                None
+            }
+        })
+        .unwrap()
+}
+
+#[test]
+fn test_deterministic_generation() {
+    let mut first = InfiniteWorld::new(7, 0.2);
+    let mut second = InfiniteWorld::new(7, 0.2);
+
+    let cells: Vec<(i64, i64)> = (-50..50)
+        .flat_map(|row| (-50..50).map(move |col| (row, col)))
+        .collect();
+    let forward: Vec<bool> = cells.iter().map(|(r, c)| first.is_mine(*r, *c)).collect();
+    let backward: Vec<bool> = cells
+        .iter()
+        .rev()
+        .map(|(r, c)| second.is_mine(*r, *c))
+        .collect();
+
+    assert!(forward.iter().eq(backward.iter().rev()));
+    assert!(forward.iter().any(|mine| *mine));
+    assert!(forward.iter().any(|mine| !*mine));
+}
+
+#[test]
+fn test_flood_fill_across_chunks() {
+    let mut world = open_world(0.15);
+
+    match world.reveal(0, 0) {
+        Reveal::Revealed(count) => assert!(count >= 9),
+        result => panic!("Unexpected reveal result: {:?}", result),
+    }
+    // The origin is a corner of four chunks
+    assert!(world.is_revealed(-1, -1));
+    assert!(world.is_revealed(-1, 0));
+    assert!(world.is_revealed(0, -1));
+    assert_eq!(world.reveal(0, 0), Reveal::AlreadyRevealed);
+}
+
+#[test]
+fn test_flood_limit() {
+    let mut world = open_world(0.01).with_flood_limit(100);
+    assert_eq!(world.reveal(0, 0), Reveal::Revealed(100));
+}
+
+#[test]
+fn test_window_verdicts() {
+    let mut world = open_world(0.15);
+    world.reveal(0, 0);
+
+    let window = world.window(-8, -8, 17, 17);
+    for row in 1..16 {
+        for col in 1..16 {
+            let (r, c) = (row as i64 - 8, col as i64 - 8);
+            if world.is_revealed(r, c) {
+                continue;
+            }
+            match check_configuration(window.clone().with_probe(row, col)) {
+                ProbeResult::Safe => assert!(!world.is_mine(r, c)),
+                ProbeResult::Unsafe => assert!(world.is_mine(r, c)),
+                _ => {}
+            }
+        }
+    }
+}
+
+#[test]
+fn test_save_load() {
+    let mut world = open_world(0.15).with_flood_limit(100);
+    world.reveal(0, 0);
+    let path = std::env::temp_dir().join(format!("minesweeper-world-{}.json", std::process::id()));
+    world.save(&path).unwrap();
+    let mut loaded = InfiniteWorld::load(&path).unwrap();
+
+    assert_eq!(loaded.window(-5, -5, 11, 11), world.window(-5, -5, 11, 11));
+    assert_eq!(loaded.reveal(0, 0), Reveal::AlreadyRevealed);
+
+    std::fs::write(
+        &path,
+        r#"{"version":2,"seed":0,"density":0.1,"flood_limit":1,"revealed":[]}"#,
+    )
+    .unwrap();
+    let error = InfiniteWorld::load(&path).unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(error.to_string(), "Unsupported save version: 2");
+}
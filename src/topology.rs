@@ -0,0 +1,398 @@
+use crate::{Col, Row};
+use std::fmt;
+use std::sync::Arc;
+
+/// A symmetry transformation of a board, see `Configuration::rotate90` and friends
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Transform {
+    /// Rotation by 90 degrees clockwise
+    Rotate90,
+    /// Reversing every row
+    MirrorH,
+    /// Reversing the order of rows
+    MirrorV,
+}
+
+impl Transform {
+    /// Maps the offset of a neighbour on the original board to its offset on the transformed one
+    pub fn offset(&self, (dr, dc): (isize, isize)) -> (isize, isize) {
+        match self {
+            Transform::Rotate90 => (dc, -dr),
+            Transform::MirrorH => (dr, -dc),
+            Transform::MirrorV => (-dr, dc),
+        }
+    }
+}
+
+/// The adjacency model of a board, i.e. which cells a number cell counts mines in
+pub trait Topology: fmt::Debug + Send + Sync {
+    /// Name of the topology used in the board header
+    fn name(&self) -> String;
+
+    /// Relative positions of the neighbours of the cell at (`row`, `col`)
+    fn offsets(&self, row: Row, col: Col) -> Vec<(isize, isize)>;
+
+    /// The topology of a board transformed by `transform`,
+    /// or `None` if it can't be expressed, e.g. for grids depending on the cell parity
+    fn transform(&self, _transform: Transform) -> Option<Arc<dyn Topology>> {
+        None
+    }
+
+    /// Number of rows in every layer of a 3D board, see `Layers`
+    fn layer_height(&self) -> Option<usize> {
+        None
+    }
+
+    /// Neighbours of the cell at (`row`, `col`) on a `rows` x `cols` board.
+    /// If `wrap` is set, neighbours wrap across the board edges as on a torus.
+    fn neighbours(
+        &self,
+        row: Row,
+        col: Col,
+        rows: usize,
+        cols: usize,
+        wrap: bool,
+    ) -> Vec<(Row, Col)> {
+        let mut result = vec![];
+
+        for (dr, dc) in self.offsets(row, col) {
+            let r = row as isize + dr;
+            let c = col as isize + dc;
+            let neighbour = if wrap {
+                (
+                    r.rem_euclid(rows as isize) as Row,
+                    c.rem_euclid(cols as isize) as Col,
+                )
+            } else if r < 0 || c < 0 || r >= rows as isize || c >= cols as isize {
+                continue;
+            } else {
+                (r as Row, c as Col)
+            };
+
+            // On small wrapped boards different offsets may lead to the same cell
+            if neighbour != (row, col) && !result.contains(&neighbour) {
+                result.push(neighbour);
+            }
+        }
+
+        result
+    }
+}
+
+/// Returns the topology with the given header `name`.
+/// Besides the fixed grids, custom neighbourhoods are written as
+/// `knight`, `ring <radius>` or `mask <row offset>,<col offset> ...`, see `NeighbourhoodMask`,
+/// and 3D boards as `layers <height>`, see `Layers`.
+pub fn from_name(name: &str) -> Arc<dyn Topology> {
+    let mut words = name.split_whitespace();
+    match (words.next(), words.next()) {
+        (Some("square8"), None) => Arc::new(Square8),
+        (Some("square4"), None) => Arc::new(Square4),
+        (Some("hex"), None) => Arc::new(Hex),
+        (Some("triangle"), None) => Arc::new(Triangle),
+        (Some("knight"), None) => Arc::new(NeighbourhoodMask::knight()),
+        (Some("layers"), Some(height)) if words.next().is_none() => match height.parse() {
+            Ok(height) if height > 0 => Arc::new(Layers::new(height)),
+            _ => panic!("Invalid layer height: {}", height),
+        },
+        (Some("ring"), Some(radius)) if words.next().is_none() => match radius.parse() {
+            Ok(radius) => Arc::new(NeighbourhoodMask::ring(radius)),
+            Err(_) => panic!("Invalid ring radius: {}", radius),
+        },
+        (Some("mask"), _) => {
+            let offsets = name["mask".len()..]
+                .split_whitespace()
+                .map(|offset| {
+                    let mut parts = offset.split(',').map(|part| part.parse::<isize>());
+                    match (parts.next(), parts.next(), parts.next()) {
+                        (Some(Ok(dr)), Some(Ok(dc)), None) => (dr, dc),
+                        _ => panic!("Invalid mask offset: {}", offset),
+                    }
+                })
+                .collect();
+            Arc::new(NeighbourhoodMask::new(offsets))
+        }
+        _ => panic!("Invalid topology: {}", name),
+    }
+}
+
+/// Square cells, eight neighbours sharing an edge or a corner (classic minesweeper)
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Square8;
+
+impl Topology for Square8 {
+    fn transform(&self, _transform: Transform) -> Option<Arc<dyn Topology>> {
+        Some(Arc::new(*self))
+    }
+
+    fn name(&self) -> String {
+        String::from("square8")
+    }
+
+    fn offsets(&self, _row: Row, _col: Col) -> Vec<(isize, isize)> {
+        vec![
+            (-1, -1),
+            (-1, 0),
+            (-1, 1),
+            (0, -1),
+            (0, 1),
+            (1, -1),
+            (1, 0),
+            (1, 1),
+        ]
+    }
+}
+
+/// Square cells, four neighbours sharing an edge
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Square4;
+
+impl Topology for Square4 {
+    fn transform(&self, _transform: Transform) -> Option<Arc<dyn Topology>> {
+        Some(Arc::new(*self))
+    }
+
+    fn name(&self) -> String {
+        String::from("square4")
+    }
+
+    fn offsets(&self, _row: Row, _col: Col) -> Vec<(isize, isize)> {
+        vec![(-1, 0), (0, -1), (0, 1), (1, 0)]
+    }
+}
+
+/// Hexagonal cells, six neighbours.
+/// Rows are laid out in the "odd-r" layout, i.e. odd rows are shifted half a cell right.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Hex;
+
+impl Topology for Hex {
+    fn name(&self) -> String {
+        String::from("hex")
+    }
+
+    fn offsets(&self, row: Row, _col: Col) -> Vec<(isize, isize)> {
+        if row.is_multiple_of(2) {
+            vec![(-1, -1), (-1, 0), (0, -1), (0, 1), (1, -1), (1, 0)]
+        } else {
+            vec![(-1, 0), (-1, 1), (0, -1), (0, 1), (1, 0), (1, 1)]
+        }
+    }
+}
+
+/// Triangular cells, twelve neighbours sharing an edge or a vertex.
+/// The cell at (`row`, `col`) points up if `row + col` is even and down otherwise.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Triangle;
+
+impl Topology for Triangle {
+    fn name(&self) -> String {
+        String::from("triangle")
+    }
+
+    fn offsets(&self, row: Row, col: Col) -> Vec<(isize, isize)> {
+        // The wide side of a triangle touches five cells of the adjacent row,
+        // its tip touches three
+        let (above, below) = if (row + col).is_multiple_of(2) {
+            (1, 2)
+        } else {
+            (2, 1)
+        };
+
+        let mut result = vec![];
+        for dc in -above..=above {
+            result.push((-1, dc));
+        }
+        for dc in [-2, -1, 1, 2].iter() {
+            result.push((0, *dc));
+        }
+        for dc in -below..=below {
+            result.push((1, dc));
+        }
+        result
+    }
+}
+
+/// Square cells with a custom set of neighbours given by their offsets,
+/// for variants where numbers count mines e.g. a knight's move away
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NeighbourhoodMask {
+    name: String,
+    offsets: Vec<(isize, isize)>,
+}
+
+impl NeighbourhoodMask {
+    pub fn new(offsets: Vec<(isize, isize)>) -> NeighbourhoodMask {
+        let mut offsets = offsets;
+        offsets.retain(|offset| *offset != (0, 0));
+        offsets.sort();
+        offsets.dedup();
+
+        let name = offsets
+            .iter()
+            .map(|(dr, dc)| format!(" {},{}", dr, dc))
+            .collect::<String>();
+        NeighbourhoodMask {
+            name: format!("mask{}", name),
+            offsets,
+        }
+    }
+
+    /// The eight cells a knight's move away
+    pub fn knight() -> NeighbourhoodMask {
+        let offsets = vec![
+            (-2, -1),
+            (-2, 1),
+            (-1, -2),
+            (-1, 2),
+            (1, -2),
+            (1, 2),
+            (2, -1),
+            (2, 1),
+        ];
+        NeighbourhoodMask {
+            name: String::from("knight"),
+            ..NeighbourhoodMask::new(offsets)
+        }
+    }
+
+    /// The cells at distance exactly `radius`, e.g. the outer ring of a 5x5 square for radius 2
+    pub fn ring(radius: usize) -> NeighbourhoodMask {
+        let r = radius as isize;
+        let mut offsets = vec![];
+        for dr in -r..=r {
+            for dc in -r..=r {
+                if dr.abs().max(dc.abs()) == r {
+                    offsets.push((dr, dc));
+                }
+            }
+        }
+        NeighbourhoodMask {
+            name: format!("ring {}", radius),
+            ..NeighbourhoodMask::new(offsets)
+        }
+    }
+}
+
+impl Topology for NeighbourhoodMask {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn offsets(&self, _row: Row, _col: Col) -> Vec<(isize, isize)> {
+        self.offsets.clone()
+    }
+
+    fn transform(&self, transform: Transform) -> Option<Arc<dyn Topology>> {
+        let mask = NeighbourhoodMask::new(
+            self.offsets
+                .iter()
+                .map(|offset| transform.offset(*offset))
+                .collect(),
+        );
+        // Keep the name of symmetric masks like `knight`
+        if mask.offsets == self.offsets {
+            Some(Arc::new(self.clone()))
+        } else {
+            Some(Arc::new(mask))
+        }
+    }
+}
+
+/// Cubic cells of a 3D board, 26 neighbours sharing a face, an edge or a corner.
+/// The layers of `height` rows each are stacked on top of each other as one tall board,
+/// so that the cell one layer down is `height` rows below. On wrapping boards the rows wrap
+/// within every layer, like the columns and the layers themselves.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Layers {
+    height: usize,
+}
+
+impl Layers {
+    pub fn new(height: usize) -> Layers {
+        assert!(height > 0, "Invalid layer height: {}", height);
+        Layers { height }
+    }
+}
+
+impl Topology for Layers {
+    fn name(&self) -> String {
+        format!("layers {}", self.height)
+    }
+
+    fn offsets(&self, row: Row, _col: Col) -> Vec<(isize, isize)> {
+        let height = self.height as isize;
+        let inner = (row % self.height) as isize;
+
+        let mut result = vec![];
+        for dl in -1..=1 {
+            for dr in -1..=1 {
+                if inner + dr < 0 || inner + dr >= height {
+                    continue;
+                }
+                for dc in -1..=1 {
+                    if (dl, dr, dc) != (0, 0, 0) {
+                        result.push((dl * height + dr, dc));
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    fn transform(&self, transform: Transform) -> Option<Arc<dyn Topology>> {
+        match transform {
+            // Reversing the rows reverses both the layers and the rows within them
+            Transform::MirrorH | Transform::MirrorV => Some(Arc::new(*self)),
+            Transform::Rotate90 => None,
+        }
+    }
+
+    fn layer_height(&self) -> Option<usize> {
+        Some(self.height)
+    }
+
+    fn neighbours(
+        &self,
+        row: Row,
+        col: Col,
+        rows: usize,
+        cols: usize,
+        wrap: bool,
+    ) -> Vec<(Row, Col)> {
+        // The layer, the row within it and the column each wrap separately
+        let shift = |value: usize, delta: isize, size: usize| {
+            let value = value as isize + delta;
+            if wrap {
+                Some(value.rem_euclid(size as isize) as usize)
+            } else if value < 0 || value >= size as isize {
+                None
+            } else {
+                Some(value as usize)
+            }
+        };
+        let (layer, inner) = (row / self.height, row % self.height);
+
+        let mut result = vec![];
+        for dl in -1..=1 {
+            for dr in -1..=1 {
+                for dc in -1..=1 {
+                    let neighbour = match (
+                        shift(layer, dl, rows / self.height),
+                        shift(inner, dr, self.height),
+                        shift(col, dc, cols),
+                    ) {
+                        (Some(l), Some(r), Some(c)) => (l * self.height + r, c),
+                        _ => continue,
+                    };
+
+                    // On small wrapped boards different offsets may lead to the same cell
+                    if neighbour != (row, col) && !result.contains(&neighbour) {
+                        result.push(neighbour);
+                    }
+                }
+            }
+        }
+        result
+    }
+}
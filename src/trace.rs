@@ -0,0 +1,141 @@
+use crate::{
+    deduce_traced, Board, Col, Configuration, Label, Row, SolveOptions, SolveStats, Square,
+};
+use std::fmt;
+
+/// A single inference of the rule engine
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeductionStep {
+    /// The iteration of the rule engine, starting from 1
+    pub iteration: usize,
+    pub reason: Reason,
+    /// The cells decided by this step
+    pub cells: Vec<(Row, Col)>,
+    /// What the cells contain: `Safe`, `Mine` or `AntiMine`
+    pub square: Square,
+}
+
+/// Why a `DeductionStep` holds
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Reason {
+    /// The number at `cell` labeled `label` needs exactly `mines` more mines
+    /// among its covered neighbours
+    Number {
+        cell: (Row, Col),
+        label: Label,
+        mines: Label,
+    },
+    /// The covered neighbours of `smaller` are among those of `larger`,
+    /// so the remaining neighbours of `larger` hold exactly `mines` mines
+    Subset {
+        smaller: ((Row, Col), Label),
+        larger: ((Row, Col), Label),
+        mines: Label,
+    },
+    /// Comparing constraints in earlier steps showed that the covered `cells`
+    /// hold exactly `mines` mines
+    Difference {
+        cells: Vec<(Row, Col)>,
+        mines: Label,
+    },
+}
+
+/// What the rule engine did, see `trace` and `iterations`
+#[derive(Debug, Default)]
+pub(crate) struct Recording {
+    pub(crate) steps: Vec<DeductionStep>,
+    /// The contents of the `squares` relation at the start of every iteration
+    pub(crate) snapshots: Vec<Vec<(Row, Col, Square)>>,
+}
+
+impl Recording {
+    fn of(board: &impl Board) -> Recording {
+        let mut recording = Recording::default();
+        deduce_traced(
+            board,
+            &SolveOptions::default(),
+            &mut SolveStats::default(),
+            Some(&mut recording),
+        )
+        .expect("No limits to interrupt the solver");
+        recording
+    }
+}
+
+/// Runs the rule engine on `board`, recording every inference it makes
+pub fn trace(board: &impl Board) -> Vec<DeductionStep> {
+    Recording::of(board).steps
+}
+
+/// Runs the rule engine on `conf`, returning the board as seen by every iteration,
+/// with the covered cells deduced so far replaced by `Safe`, `Mine` or `AntiMine`.
+/// The first board is `conf` itself.
+pub fn iterations(conf: &Configuration) -> Vec<Configuration> {
+    Recording::of(conf)
+        .snapshots
+        .into_iter()
+        .map(|snapshot| {
+            let mut board = conf.clone();
+            for (row, col, square) in snapshot {
+                if matches!(square, Square::Safe | Square::Mine | Square::AntiMine) {
+                    board.board[row][col] = square;
+                }
+            }
+            board
+        })
+        .collect()
+}
+
+/// Renders `steps` as text, one step per line
+pub fn render_trace(steps: &[DeductionStep]) -> String {
+    steps.iter().map(|step| format!("{}\n", step)).collect()
+}
+
+impl fmt::Display for DeductionStep {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cells = |cells: &[(Row, Col)]| {
+            cells
+                .iter()
+                .map(|(row, col)| format!("({},{})", row, col))
+                .collect::<Vec<String>>()
+                .join(",")
+        };
+        match &self.reason {
+            Reason::Number {
+                cell: (row, col),
+                label,
+                mines,
+            } => write!(
+                f,
+                "cell ({},{})={} needs {} more mines among its covered neighbours",
+                row, col, label, mines
+            )?,
+            Reason::Subset {
+                smaller: ((small_row, small_col), small_label),
+                larger: ((large_row, large_col), large_label),
+                mines,
+            } => write!(
+                f,
+                "the covered neighbours of ({},{})={} are among those of ({},{})={}, \
+                 so the rest hold {} mines",
+                small_row, small_col, small_label, large_row, large_col, large_label, mines
+            )?,
+            Reason::Difference {
+                cells: covered,
+                mines,
+            } => write!(
+                f,
+                "earlier comparisons show that {} hold {} mines",
+                cells(covered),
+                mines
+            )?,
+        }
+        let square = match self.square {
+            Square::Safe => "safe",
+            Square::Mine => "mines",
+            Square::AntiMine => "anti-mines",
+            _ => unreachable!("Only safe cells, mines and anti-mines are deduced"),
+        };
+        write!(f, " ⇒ {} {}", cells(&self.cells), square)
+    }
+}
@@ -0,0 +1,216 @@
+use crate::topology::Transform;
+use crate::{Board, Col, Configuration, Row, Square, Topology};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// A rectangular region of a board
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Rect {
+    pub row: Row,
+    pub col: Col,
+    pub rows: usize,
+    pub cols: usize,
+}
+
+impl Rect {
+    pub fn new(row: Row, col: Col, rows: usize, cols: usize) -> Rect {
+        Rect {
+            row,
+            col,
+            rows,
+            cols,
+        }
+    }
+
+    pub fn contains(&self, row: Row, col: Col) -> bool {
+        row >= self.row
+            && row < self.row + self.rows
+            && col >= self.col
+            && col < self.col + self.cols
+    }
+}
+
+impl Configuration {
+    /// Returns the part of the board inside `rect`, clamped to the board.
+    /// On 3D boards the part is extended to whole layers, see `crop_bounds`.
+    /// A number whose neighbours are not the same on the cropped board is replaced with
+    /// a safe cell, so the cropped board never implies more than the original one.
+    /// The cropped board never wraps around.
+    pub fn crop(&self, rect: Rect) -> Configuration {
+        window(
+            self,
+            self.crop_bounds(rect),
+            self.topology.clone(),
+            self.anti_mines,
+        )
+    }
+
+    /// Returns the part of the board `crop` keeps for `rect`: `rect` clamped to the board and,
+    /// if the topology has layers, extended to whole layers, which it can't split
+    pub(crate) fn crop_bounds(&self, rect: Rect) -> Rect {
+        let mut top = rect.row;
+        let mut bottom = self.rows().min(rect.row + rect.rows).max(top);
+        let cols = self
+            .cols()
+            .min(rect.col + rect.cols)
+            .saturating_sub(rect.col);
+        if let Some(height) = self.topology.layer_height() {
+            if bottom > top {
+                top = top / height * height;
+                bottom = bottom.div_ceil(height) * height;
+            }
+        }
+        Rect::new(top, rect.col, bottom - top, cols)
+    }
+
+    /// Returns the board rotated by 90 degrees clockwise
+    pub fn rotate90(&self) -> Configuration {
+        let rows = self.rows();
+        let board = (0..self.cols())
+            .map(|row| {
+                (0..rows)
+                    .map(|col| self.board[rows - 1 - col][row])
+                    .collect()
+            })
+            .collect();
+        self.transformed(board, Transform::Rotate90)
+    }
+
+    /// Returns the board mirrored horizontally, i.e. with every row reversed
+    pub fn mirror_h(&self) -> Configuration {
+        let board = self
+            .board
+            .iter()
+            .map(|row| row.iter().rev().cloned().collect())
+            .collect();
+        self.transformed(board, Transform::MirrorH)
+    }
+
+    /// Returns the board mirrored vertically, i.e. with the rows in reverse order
+    pub fn mirror_v(&self) -> Configuration {
+        let board = self.board.iter().rev().cloned().collect();
+        self.transformed(board, Transform::MirrorV)
+    }
+
+    fn transformed(&self, board: Vec<Vec<Square>>, transform: Transform) -> Configuration {
+        let topology = self.topology.transform(transform).unwrap_or_else(|| {
+            panic!(
+                "Topology {} doesn't support {:?}",
+                self.topology.name(),
+                transform
+            )
+        });
+        Configuration::new(board, topology, self.wrap, self.anti_mines)
+    }
+
+    /// Returns the smallest part of the board containing the probe and
+    /// all number cells with covered neighbours together with their neighbours.
+    /// Numbers without covered neighbours carry no constraints and are replaced with safe cells.
+    pub fn restrict_to_frontier(&self) -> Configuration {
+        let mut relevant = HashSet::new();
+        let mut frontier = HashSet::new();
+        for (row, squares) in self.board.iter().enumerate() {
+            for (col, square) in squares.iter().enumerate() {
+                match square {
+                    Square::Probe => {
+                        relevant.insert((row, col));
+                    }
+                    Square::Number(_) => {
+                        let neighbours = self.neighbours(row, col);
+                        let is_frontier = neighbours.iter().any(|(r, c)| {
+                            matches!(self.board[*r][*c], Square::Empty | Square::Probe)
+                        });
+                        if is_frontier {
+                            frontier.insert((row, col));
+                            relevant.insert((row, col));
+                            relevant.extend(neighbours);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // Numbers next to the frontier may lose neighbours to the cropping,
+        // so all numbers without covered neighbours are replaced
+        let mut conf = self.clone();
+        for (row, squares) in conf.board.iter_mut().enumerate() {
+            for (col, square) in squares.iter_mut().enumerate() {
+                if let Square::Number(_) = square {
+                    if !frontier.contains(&(row, col)) {
+                        *square = Square::Safe;
+                    }
+                }
+            }
+        }
+
+        // A wrapping frontier has no meaningful bounding box
+        if self.wrap || relevant.is_empty() {
+            return conf;
+        }
+
+        let min_row = relevant.iter().map(|(row, _)| *row).min().unwrap();
+        let max_row = relevant.iter().map(|(row, _)| *row).max().unwrap();
+        let min_col = relevant.iter().map(|(_, col)| *col).min().unwrap();
+        let max_col = relevant.iter().map(|(_, col)| *col).max().unwrap();
+        conf.crop(Rect::new(
+            min_row,
+            min_col,
+            max_row - min_row + 1,
+            max_col - min_col + 1,
+        ))
+    }
+}
+
+/// Returns the part of `board` inside `rect`, which must lie within the board.
+/// A number whose neighbours are not the same on the window is replaced with a safe cell.
+pub(crate) fn window(
+    board: &impl Board,
+    rect: Rect,
+    topology: Arc<dyn Topology>,
+    anti_mines: bool,
+) -> Configuration {
+    let squares: Vec<Vec<Square>> = (rect.row..rect.row + rect.rows)
+        .map(|row| {
+            (rect.col..rect.col + rect.cols)
+                .map(|col| board.square(row, col))
+                .collect()
+        })
+        .collect();
+
+    // The numbers are put back once their neighbours are known to be the same,
+    // as numbers with fewer neighbours than mines are rejected
+    let safe = squares
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|square| match square {
+                    Square::Number(_) => Square::Safe,
+                    square => *square,
+                })
+                .collect()
+        })
+        .collect();
+    let mut window = Configuration::new(safe, topology, false, anti_mines);
+
+    for (row, squares) in squares.iter().enumerate() {
+        for (col, square) in squares.iter().enumerate() {
+            if let Square::Number(_) = square {
+                let original: HashSet<(Row, Col)> = board
+                    .neighbours(rect.row + row, rect.col + col)
+                    .into_iter()
+                    .collect();
+                let kept: HashSet<(Row, Col)> = window
+                    .neighbours(row, col)
+                    .into_iter()
+                    .map(|(r, c)| (rect.row + r, rect.col + c))
+                    .collect();
+                if original == kept {
+                    window.board[row][col] = *square;
+                }
+            }
+        }
+    }
+
+    window
+}
@@ -0,0 +1,79 @@
+//! Cross-checking of boards against the mine layout behind them, for generators and replays.
+
+use crate::{Col, Configuration, Label, MineLayout, Row, Square};
+
+/// A way in which a board contradicts its mine layout, see `validate_against`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// The board and the layout have different dimensions, nothing else is checked
+    SizeMismatch {
+        rows: usize,
+        cols: usize,
+        layout_rows: usize,
+        layout_cols: usize,
+    },
+    /// A number doesn't match the mines around it
+    WrongNumber {
+        row: Row,
+        col: Col,
+        shown: Label,
+        actual: Label,
+    },
+    /// A revealed mine-free cell is a mine
+    RevealedMine { row: Row, col: Col },
+    /// A mine shown on the board is not a mine
+    FalseMine { row: Row, col: Col },
+}
+
+/// Checks that `conf` could have been revealed from `layout`: every number matches the mines
+/// around it and no revealed mine-free cell is a mine, nor any shown mine mine-free.
+/// Neighbours follow the topology of the board. Layouts have no anti-mines, so anti-mine squares
+/// are checked as mine-free cells and count against the numbers around them.
+/// Returns the violations ordered by position, or nothing if the board is valid.
+pub fn validate_against(conf: &Configuration, layout: &MineLayout) -> Vec<Violation> {
+    if (conf.rows(), conf.cols()) != (layout.rows(), layout.cols()) {
+        return vec![Violation::SizeMismatch {
+            rows: conf.rows(),
+            cols: conf.cols(),
+            layout_rows: layout.rows(),
+            layout_cols: layout.cols(),
+        }];
+    }
+
+    let mut violations = vec![];
+    for (row, squares) in conf.board.iter().enumerate() {
+        for (col, square) in squares.iter().enumerate() {
+            let is_mine = layout.is_mine(row, col);
+            match square {
+                Square::Number(_) | Square::Safe | Square::AntiMine if is_mine => {
+                    violations.push(Violation::RevealedMine { row, col });
+                }
+                Square::Mine if !is_mine => {
+                    violations.push(Violation::FalseMine { row, col });
+                }
+                _ => {}
+            }
+
+            if let Square::Number(shown) = *square {
+                let actual = conf
+                    .neighbours(row, col)
+                    .into_iter()
+                    .map(|(r, c)| match conf.board[r][c] {
+                        Square::AntiMine => -1,
+                        _ if layout.is_mine(r, c) => 1,
+                        _ => 0,
+                    })
+                    .sum();
+                if shown != actual {
+                    violations.push(Violation::WrongNumber {
+                        row,
+                        col,
+                        shown,
+                        actual,
+                    });
+                }
+            }
+        }
+    }
+    violations
+}
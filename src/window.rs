@@ -0,0 +1,124 @@
+//! Analysis of a part of a huge board, so that UIs stay responsive on expert+ boards.
+
+use crate::{deduce, Col, Configuration, ProbeResult, Rect, Row, Square};
+use std::collections::HashSet;
+
+/// What `analyze_window` assumes about the covered cells around the window
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BoundaryPolicy {
+    /// The cells around the window may or may not be mines
+    Unconstrained,
+    /// The covered cells around the window are mines
+    Pessimistic,
+    /// The covered cells around the window are safe
+    Optimistic,
+}
+
+/// A covered cell of the window decided by `analyze_window`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct WindowVerdict {
+    pub row: Row,
+    pub col: Col,
+    /// `Safe` or `Unsafe`
+    pub verdict: ProbeResult,
+    /// The verdict doesn't depend on the boundary assumption,
+    /// so it holds on the whole board as well
+    pub stable: bool,
+}
+
+/// Runs the rule engine only on the numbers inside `rect`, treating the covered cells around it
+/// as `boundary` says. Returns the decided covered cells of the window in board coordinates.
+/// Numbers outside the window are ignored, so only the stable verdicts are guaranteed to hold
+/// on the whole board; the others follow from the boundary assumption.
+pub fn analyze_window(
+    conf: &Configuration,
+    rect: Rect,
+    boundary: BoundaryPolicy,
+) -> Vec<WindowVerdict> {
+    assert!(!conf.wrap(), "Windows of wrapping boards are not supported");
+
+    let verdicts = decide(conf, rect, boundary);
+    let stable: HashSet<(Row, Col, ProbeResult)> = if boundary == BoundaryPolicy::Unconstrained {
+        verdicts.iter().cloned().collect()
+    } else {
+        decide(conf, rect, BoundaryPolicy::Unconstrained)
+            .into_iter()
+            .collect()
+    };
+
+    verdicts
+        .into_iter()
+        .map(|(row, col, verdict)| WindowVerdict {
+            row,
+            col,
+            verdict,
+            stable: stable.contains(&(row, col, verdict)),
+        })
+        .collect()
+}
+
+/// Decides the covered cells of the window assuming `boundary`, sorted by position
+fn decide(
+    conf: &Configuration,
+    rect: Rect,
+    boundary: BoundaryPolicy,
+) -> Vec<(Row, Col, ProbeResult)> {
+    let cells: Vec<(Row, Col)> = (rect.row..conf.rows().min(rect.row + rect.rows))
+        .flat_map(|row| {
+            (rect.col..conf.cols().min(rect.col + rect.cols)).map(move |col| (row, col))
+        })
+        .collect();
+    if cells.is_empty() {
+        return vec![];
+    }
+
+    // The window together with the neighbours of its cells
+    let mut around = cells.clone();
+    for &(row, col) in &cells {
+        around.extend(conf.neighbours(row, col));
+    }
+    let min_row = around.iter().map(|(row, _)| *row).min().unwrap();
+    let max_row = around.iter().map(|(row, _)| *row).max().unwrap();
+    let min_col = around.iter().map(|(_, col)| *col).min().unwrap();
+    let max_col = around.iter().map(|(_, col)| *col).max().unwrap();
+    let bounds = conf.crop_bounds(Rect::new(
+        min_row,
+        min_col,
+        max_row - min_row + 1,
+        max_col - min_col + 1,
+    ));
+
+    let mut assumed = conf.clone();
+    for row in bounds.row..bounds.row + bounds.rows {
+        for col in bounds.col..bounds.col + bounds.cols {
+            if rect.contains(row, col) {
+                continue;
+            }
+            let square = &mut assumed.board[row][col];
+            *square = match (*square, boundary) {
+                (Square::Number(_), _) => Square::Safe,
+                (Square::Empty | Square::Probe, BoundaryPolicy::Unconstrained) => Square::Empty,
+                (Square::Empty | Square::Probe, BoundaryPolicy::Pessimistic) => Square::Mine,
+                (Square::Empty | Square::Probe, BoundaryPolicy::Optimistic) => Square::Safe,
+                (square, _) => square,
+            };
+        }
+    }
+
+    let window = assumed.crop(bounds);
+    let verified = deduce(&window);
+    let mut verdicts: Vec<(Row, Col, ProbeResult)> = verified
+        .into_iter()
+        .map(|((row, col), square)| (row + bounds.row, col + bounds.col, square))
+        .filter(|&(row, col, _)| rect.contains(row, col))
+        .map(|(row, col, square)| {
+            let verdict = match square {
+                Square::Safe => ProbeResult::Safe,
+                _ => ProbeResult::Unsafe,
+            };
+            (row, col, verdict)
+        })
+        .collect();
+    verdicts.sort_by_key(|&(row, col, _)| (row, col));
+    verdicts
+}
@@ -0,0 +1,208 @@
+use crate::random::splitmix64;
+use crate::topology::Square8;
+use crate::{Configuration, Label, Square};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Side of a square chunk of cells generated at once
+const CHUNK_SIZE: i64 = 32;
+
+/// Version of the save file format, bumped on incompatible changes
+const SAVE_VERSION: u32 = 1;
+
+/// The contents of a save file. The mines aren't stored, they are regenerated from the seed.
+#[derive(Serialize, Deserialize)]
+struct SaveFile {
+    version: u32,
+    seed: u64,
+    density: f64,
+    flood_limit: usize,
+    revealed: Vec<(i64, i64)>,
+}
+
+/// The outcome of revealing a cell
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Reveal {
+    /// The cell is a mine
+    Mine,
+    /// The cell was already revealed
+    AlreadyRevealed,
+    /// The number of newly revealed cells, including the ones opened by the flood fill
+    Revealed(usize),
+}
+
+/// An endless board whose mines are generated lazily, chunk by chunk, from a seed.
+/// The same seed always produces the same world regardless of the order cells are visited in.
+/// Cells are addressed by signed coordinates, the world extends in all directions.
+#[derive(Clone, Debug)]
+pub struct InfiniteWorld {
+    seed: u64,
+    density: f64,
+    /// Maximal number of cells opened by a single reveal
+    flood_limit: usize,
+    chunks: HashMap<(i64, i64), Vec<bool>>,
+    revealed: HashSet<(i64, i64)>,
+}
+
+impl InfiniteWorld {
+    /// Creates a world where every cell is a mine with probability `density`
+    pub fn new(seed: u64, density: f64) -> InfiniteWorld {
+        assert!(
+            density > 0.0 && density < 1.0,
+            "Invalid mine density: {}",
+            density
+        );
+        InfiniteWorld {
+            seed,
+            density,
+            flood_limit: 10_000,
+            chunks: HashMap::new(),
+            revealed: HashSet::new(),
+        }
+    }
+
+    /// Returns the same world opening at most `limit` cells per reveal.
+    /// On sparse worlds the zero regions may be arbitrarily large, so the flood fill has to stop somewhere.
+    pub fn with_flood_limit(self, limit: usize) -> InfiniteWorld {
+        InfiniteWorld {
+            flood_limit: limit,
+            ..self
+        }
+    }
+
+    pub fn is_mine(&mut self, row: i64, col: i64) -> bool {
+        let key = (row.div_euclid(CHUNK_SIZE), col.div_euclid(CHUNK_SIZE));
+        let (seed, density) = (self.seed, self.density);
+        let chunk = self
+            .chunks
+            .entry(key)
+            .or_insert_with(|| generate_chunk(seed, density, key));
+        let index = row.rem_euclid(CHUNK_SIZE) * CHUNK_SIZE + col.rem_euclid(CHUNK_SIZE);
+        chunk[index as usize]
+    }
+
+    pub fn is_revealed(&self, row: i64, col: i64) -> bool {
+        self.revealed.contains(&(row, col))
+    }
+
+    pub fn mines_around(&mut self, row: i64, col: i64) -> Label {
+        neighbours(row, col)
+            .filter(|(r, c)| self.is_mine(*r, *c))
+            .count() as Label
+    }
+
+    /// Reveals the cell at (`row`, `col`). If it has no mines around,
+    /// its neighbours are revealed as well, continuing across chunk boundaries.
+    pub fn reveal(&mut self, row: i64, col: i64) -> Reveal {
+        if self.is_revealed(row, col) {
+            return Reveal::AlreadyRevealed;
+        }
+        if self.is_mine(row, col) {
+            return Reveal::Mine;
+        }
+
+        let mut count = 0;
+        let mut queue = VecDeque::new();
+        self.revealed.insert((row, col));
+        queue.push_back((row, col));
+
+        while let Some((r, c)) = queue.pop_front() {
+            count += 1;
+            if self.mines_around(r, c) != 0 {
+                continue;
+            }
+            for neighbour in neighbours(r, c) {
+                if count + queue.len() >= self.flood_limit {
+                    break;
+                }
+                if self.revealed.insert(neighbour) {
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+
+        Reveal::Revealed(count)
+    }
+
+    /// Saves the world and the revealed cells to `path` as JSON, so that the game can be resumed
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut revealed: Vec<(i64, i64)> = self.revealed.iter().copied().collect();
+        revealed.sort();
+        let save = SaveFile {
+            version: SAVE_VERSION,
+            seed: self.seed,
+            density: self.density,
+            flood_limit: self.flood_limit,
+            revealed,
+        };
+        fs::write(path, serde_json::to_string(&save)?)
+    }
+
+    /// Loads a world saved by `save`
+    pub fn load(path: impl AsRef<Path>) -> io::Result<InfiniteWorld> {
+        let save: SaveFile = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        if save.version != SAVE_VERSION {
+            return Err(invalid(format!(
+                "Unsupported save version: {}",
+                save.version
+            )));
+        }
+        if !(save.density > 0.0 && save.density < 1.0) {
+            return Err(invalid(format!("Invalid mine density: {}", save.density)));
+        }
+
+        let mut world =
+            InfiniteWorld::new(save.seed, save.density).with_flood_limit(save.flood_limit);
+        world.revealed = save.revealed.into_iter().collect();
+        Ok(world)
+    }
+
+    /// Returns the `rows` x `cols` part of the world with the top left corner at (`row`, `col`)
+    /// as seen by the player: revealed cells are labeled with the number of mines around,
+    /// everything else is covered. Numbers on the edges of the window are replaced with safe cells,
+    /// as their neighbours are not all on the window.
+    pub fn window(&mut self, row: i64, col: i64, rows: usize, cols: usize) -> Configuration {
+        let mut board = vec![vec![Square::Empty; cols]; rows];
+
+        for (i, squares) in board.iter_mut().enumerate() {
+            for (j, square) in squares.iter_mut().enumerate() {
+                let (r, c) = (row + i as i64, col + j as i64);
+                if !self.is_revealed(r, c) {
+                    continue;
+                }
+                let is_edge = i == 0 || j == 0 || i + 1 == rows || j + 1 == cols;
+                *square = if is_edge {
+                    Square::Safe
+                } else {
+                    Square::Number(self.mines_around(r, c))
+                };
+            }
+        }
+
+        Configuration::new(board, Arc::new(Square8), false, false)
+    }
+}
+
+fn neighbours(row: i64, col: i64) -> impl Iterator<Item = (i64, i64)> {
+    (-1..=1)
+        .flat_map(|dr| (-1..=1).map(move |dc| (dr, dc)))
+        .filter(|offset| *offset != (0, 0))
+        .map(move |(dr, dc)| (row + dr, col + dc))
+}
+
+/// Generates the mines of the chunk at `key` from the world `seed`
+fn generate_chunk(seed: u64, density: f64, (chunk_row, chunk_col): (i64, i64)) -> Vec<bool> {
+    let mut state = seed
+        ^ (chunk_row as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (chunk_col as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    let threshold = (density * u64::MAX as f64) as u64;
+
+    (0..CHUNK_SIZE * CHUNK_SIZE)
+        .map(|_| splitmix64(&mut state) < threshold)
+        .collect()
+}